@@ -70,34 +70,325 @@
 ///
 /// Find the top three Elves carrying the most Calories. How many Calories are those Elves carrying
 /// in total?
+use crate::util::split_on_blank_line;
+use crate::DayResult;
+use std::collections::{BTreeMap, BinaryHeap, HashMap};
+use std::io::{self, Write};
+use std::iter;
 
-const INPUT: &str = include_str!("../input/day_01");
+#[cfg(feature = "bundled-input")]
+pub const INPUT: &str = include_str!("../input/day_01");
 
+#[cfg(feature = "bundled-input")]
 pub fn run() {
-    let elves = load_calories(INPUT);
-    let mut elves_calories_totalled: Vec<u32> =
-        elves.iter().map(|calories| calories.iter().sum()).collect();
-    elves_calories_totalled.sort();
-    elves_calories_totalled.reverse();
+    run_with_input(INPUT, &mut io::stdout()).expect("Couldn't write the answers");
+}
 
-    let biggest_total_calories = elves_calories_totalled.get(0).unwrap();
+pub fn run_with_input(input: &str, out: &mut dyn Write) -> io::Result<()> {
+    let result = solve(input);
 
-    println!(
+    writeln!(
+        out,
         "The calories carried by the Elf that is carrying the most is: {}",
-        biggest_total_calories
-    );
+        result.part_one
+    )?;
+
+    writeln!(
+        out,
+        "The total calories carried by the top three Elves is: {}",
+        result.part_two
+    )?;
+
+    writeln!(
+        out,
+        "The total calories carried by all the Elves is: {}",
+        grand_total(&load_calories(input))
+    )?;
+    Ok(())
+}
+
+pub fn solve(input: &str) -> DayResult {
+    let elves = load_calories(input);
+    let mut elves_calories_totalled = totals(&elves);
+    elves_calories_totalled.sort();
+    elves_calories_totalled.reverse();
+
+    let biggest_total_calories = elves_calories_totalled.first().copied().unwrap_or(0);
 
     let top_three_elves: u32 = elves_calories_totalled.iter().take(3).sum();
 
-    println!(
-        "The total calories carried by the top three Elves is: {}",
-        top_three_elves
-    );
+    DayResult::new(biggest_total_calories, top_three_elves)
 }
 
-fn load_calories(input: &str) -> Vec<Vec<u32>> {
+pub fn totals(elves: &[Vec<u32>]) -> Vec<u32> {
+    elves.iter().map(|calories| calories.iter().sum()).collect()
+}
+
+/// The calories of every item carried by every Elf, as a `u64` so it can't overflow even when the
+/// totals of single Elves fit in a `u32`.
+pub fn grand_total(elves: &[Vec<u32>]) -> u64 {
+    elves
+        .iter()
+        .flatten()
+        .map(|&calories| u64::from(calories))
+        .sum()
+}
+
+/// The three largest totals, largest first, going through the input Elf by Elf. Only the totals of
+/// the current Elf and the top three are kept, so this works for any amount of Elves. Missing
+/// Elves count as carrying nothing.
+pub fn stream_top3(input: &str) -> [u32; 3] {
+    let mut top = [0; 3];
+    let mut keep = |total: u32| {
+        if let Some(position) = top.iter().position(|&t| total > t) {
+            top[position..].rotate_right(1);
+            top[position] = total;
+        }
+    };
+
+    // lines are split up and parsed like `load_calories` does, so both agree on every input
+    let mut total = None;
+    for line in input.lines() {
+        let line = line.trim_end_matches('\r');
+        if line.trim().is_empty() {
+            if let Some(total) = total.take() {
+                keep(total);
+            }
+        } else {
+            let total = total.get_or_insert(0);
+            if let Ok(calories) = line.parse::<u32>() {
+                *total = total.saturating_add(calories);
+            }
+        }
+    }
+    if let Some(total) = total {
+        keep(total);
+    }
+    top
+}
+
+/// The k-th largest total (starting at 1 for the largest), without sorting all totals.
+pub fn kth_largest_total(totals: &[u32], k: usize) -> Option<u32> {
+    if k == 0 || k > totals.len() {
+        return None;
+    }
+    let mut totals = totals.to_vec();
+    let (_, kth, _) = totals.select_nth_unstable_by(k - 1, |a, b| b.cmp(a));
+    Some(*kth)
+}
+
+/// The index, starting at 0, and total of every Elf carrying more than `threshold` calories, in
+/// the order of the input.
+pub fn elves_above(totals: &[u32], threshold: u32) -> Vec<(usize, u32)> {
+    totals
+        .iter()
+        .copied()
+        .enumerate()
+        .filter(|&(_, total)| total > threshold)
+        .collect()
+}
+
+/// The population standard deviation of the totals, 0 when there are fewer than two.
+pub fn total_std_dev(totals: &[u32]) -> f64 {
+    if totals.len() < 2 {
+        return 0.0;
+    }
+    let count = totals.len() as f64;
+    let mean = totals.iter().map(|&total| f64::from(total)).sum::<f64>() / count;
+    let variance = totals
+        .iter()
+        .map(|&total| (f64::from(total) - mean).powi(2))
+        .sum::<f64>()
+        / count;
+    variance.sqrt()
+}
+
+/// How many Elves fall in each range of `bucket_size` calories, keyed by the start of the range.
+/// Empty ranges are left out.
+///
+/// Panics when `bucket_size` is 0.
+pub fn histogram(totals: &[u32], bucket_size: u32) -> BTreeMap<u32, usize> {
+    assert!(bucket_size > 0, "the bucket size must be positive");
+    let mut buckets = BTreeMap::new();
+    for total in totals {
+        *buckets
+            .entry(total / bucket_size * bucket_size)
+            .or_insert(0) += 1;
+    }
+    buckets
+}
+
+/// The fewest Elves whose totals together reach at least `target` calories, taking the Elves
+/// carrying the most first. `None` when all of them together fall short.
+pub fn elves_needed_for(totals: &[u32], target: u32) -> Option<usize> {
+    let mut totals = totals.to_vec();
+    totals.sort_unstable_by(|a, b| b.cmp(a));
+    let mut sum = 0u64;
+    for (count, total) in iter::once(0).chain(totals).enumerate() {
+        sum += u64::from(total);
+        if sum >= u64::from(target) {
+            return Some(count);
+        }
+    }
+    None
+}
+
+/// Every Elf as `(rank, elf_index, total)`, from the most calories to the least. Ranks start at 1
+/// and indices at 0. Elves with equal totals keep their order from the input.
+pub fn leaderboard(elves: &[Vec<u32>]) -> Vec<(usize, usize, u32)> {
+    let mut indexed: Vec<(usize, u32)> = totals(elves).into_iter().enumerate().collect();
+    indexed.sort_by(|(_, a), (_, b)| b.cmp(a));
+    indexed
+        .into_iter()
+        .enumerate()
+        .map(|(rank, (elf, total))| (rank + 1, elf, total))
+        .collect()
+}
+
+/// The totals in a max-heap, for answering many top-k questions about the same input. Popping
+/// the heap gives the totals in descending order.
+pub fn totals_heap(elves: &[Vec<u32>]) -> BinaryHeap<u32> {
+    totals(elves).into_iter().collect()
+}
+
+/// The sum of the `k` largest totals in the heap, or of all of them when there are fewer. The heap
+/// itself is left untouched, so it can be queried again.
+pub fn sum_top_k(heap: &BinaryHeap<u32>, k: usize) -> u32 {
+    let mut heap = heap.clone();
+    (0..k).map_while(|_| heap.pop()).sum()
+}
+
+/// The largest single food item as `(elf_index, item_index, calories)`, both indices starting at
+/// 0. When several items are equally large the first one is returned.
+pub fn largest_item(elves: &[Vec<u32>]) -> Option<(usize, usize, u32)> {
+    elves
+        .iter()
+        .enumerate()
+        .flat_map(|(elf, calories)| {
+            calories
+                .iter()
+                .enumerate()
+                .map(move |(item, &calories)| (elf, item, calories))
+        })
+        .fold(None, |largest, candidate| match largest {
+            Some((_, _, calories)) if calories >= candidate.2 => largest,
+            _ => Some(candidate),
+        })
+}
+
+/// Repacks the food items over as few Elves as it can, without any Elf carrying more than `cap`
+/// calories. An item that's larger than the cap on its own gets an Elf to itself.
+///
+/// This is bin packing, which is too hard to solve exactly for a large amount of items, so it
+/// uses the first-fit decreasing heuristic instead: going from the largest item to the smallest,
+/// every item goes to the first Elf that still has room for it. That can take more Elves than
+/// needed, but never more than about 11/9 times the optimal amount.
+pub fn repack(items: &[u32], cap: u32) -> Vec<Vec<u32>> {
+    let mut items = items.to_vec();
+    items.sort_unstable_by(|a, b| b.cmp(a));
+
+    let mut elves: Vec<(u32, Vec<u32>)> = Vec::new();
+    for item in items {
+        let room = elves
+            .iter_mut()
+            .find(|(load, _)| load.checked_add(item).is_some_and(|load| load <= cap));
+        match room {
+            Some((load, elf)) => {
+                *load += item;
+                elf.push(item);
+            }
+            None => elves.push((item, vec![item])),
+        }
+    }
+    elves.into_iter().map(|(_, elf)| elf).collect()
+}
+
+#[derive(Debug, PartialEq)]
+pub enum MergeError {
+    /// There's no Elf at the index.
+    NoElf(usize),
+    /// An Elf can't be merged with itself.
+    SameElf(usize),
+}
+
+/// The Elves with `a` and `b` pooling their food, as one Elf carrying the items of `a` followed by
+/// those of `b`. The pooled Elf takes the place of the first of the two, the other one is removed.
+pub fn merge_elves(elves: &[Vec<u32>], a: usize, b: usize) -> Result<Vec<Vec<u32>>, MergeError> {
+    if let Some(&index) = [a, b].iter().find(|&&index| index >= elves.len()) {
+        return Err(MergeError::NoElf(index));
+    }
+    if a == b {
+        return Err(MergeError::SameElf(a));
+    }
+    let mut merged = elves.to_vec();
+    let pooled = [elves[a].as_slice(), elves[b].as_slice()].concat();
+    merged[a.min(b)] = pooled;
+    merged.remove(a.max(b));
+    Ok(merged)
+}
+
+/// Every pair of Elves carrying the exact same items in the same order, as `(first, second)`
+/// indices starting at 0, like a block that got pasted twice. Elves without any items are left out.
+pub fn duplicate_elves(elves: &[Vec<u32>]) -> Vec<(usize, usize)> {
+    let mut seen: HashMap<&[u32], Vec<usize>> = HashMap::new();
+    let mut duplicates = Vec::new();
+    for (elf, items) in elves.iter().enumerate() {
+        if items.is_empty() {
+            continue;
+        }
+        let same = seen.entry(items).or_default();
+        duplicates.extend(same.iter().map(|&earlier| (earlier, elf)));
+        same.push(elf);
+    }
+    duplicates.sort_unstable();
+    duplicates
+}
+
+/// Checks that the input consists of blocks of numbers, one number per line.
+pub fn validate(input: &str) -> Vec<String> {
     input
-        .split("\n\n")
+        .lines()
+        .enumerate()
+        // a line of only whitespace separates the Elves like an empty line does
+        .filter(|(_, line)| !line.trim().is_empty() && line.parse::<u32>().is_err())
+        .map(|(i, line)| format!("line {}: '{}' is not a number", i + 1, line))
+        .collect()
+}
+
+fn load_calories(input: &str) -> Vec<Vec<u32>> {
+    load_calories_sep(input, "\n\n")
+}
+
+/// Like `load_calories`, but with the Elves separated by another line, like `---`. Whitespace around
+/// the separator is left out, so `"\n\n"` is a blank line. Several separators in a row separate
+/// the Elves like a single one does.
+pub fn load_calories_sep(input: &str, separator: &str) -> Vec<Vec<u32>> {
+    let separator = separator.trim();
+    if separator.is_empty() {
+        return split_on_blank_line(input)
+            .into_iter()
+            .map(|elf| elf.lines().filter_map(|food| food.parse().ok()).collect())
+            .collect();
+    }
+
+    let mut elves = Vec::new();
+    let mut elf: Option<Vec<u32>> = None;
+    for line in input.lines() {
+        if line.trim() == separator {
+            elves.extend(elf.take());
+        } else {
+            elf.get_or_insert_with(Vec::new)
+                .extend(line.parse::<u32>().ok());
+        }
+    }
+    elves.extend(elf);
+    elves
+}
+
+/// Like `load_calories`, but also accepts negative lines for food an Elf has dropped.
+pub fn load_calories_signed(input: &str) -> Vec<Vec<i64>> {
+    split_on_blank_line(input)
+        .into_iter()
         .map(|elf| {
             elf.lines()
                 .map(|food| food.parse())
@@ -107,10 +398,17 @@ fn load_calories(input: &str) -> Vec<Vec<u32>> {
         .collect()
 }
 
+/// Like `totals`, but for `load_calories_signed`, where dropped food counts against an Elf.
+pub fn totals_signed(elves: &[Vec<i64>]) -> Vec<i64> {
+    elves.iter().map(|calories| calories.iter().sum()).collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    const EXAMPLE: &str = "1000\n2000\n3000\n\n4000\n\n5000\n6000\n\n7000\n8000\n9000\n\n10000";
+
     #[test]
     fn test_load_calories() {
         let input = "1000\n2000\n3000\n\n4000\n\n5000\n6000\n\n7000\n8000\n9000\n\n10000";
@@ -124,4 +422,248 @@ mod tests {
         ];
         assert_eq!(load_calories(input), expected);
     }
+
+    #[test]
+    fn test_load_calories_signed() {
+        let input = "1000\n-500\n3000\n\n4000";
+
+        let elves = load_calories_signed(input);
+
+        assert_eq!(elves, vec![vec![1000, -500, 3000], vec![4000]]);
+        assert_eq!(totals_signed(&elves), vec![3500, 4000]);
+    }
+
+    #[test]
+    fn test_kth_largest_total() {
+        let totals = totals(&load_calories(EXAMPLE));
+
+        assert_eq!(totals, vec![6000, 4000, 11000, 24000, 10000]);
+        assert_eq!(kth_largest_total(&totals, 1), Some(24000));
+        assert_eq!(kth_largest_total(&totals, 3), Some(10000));
+        assert_eq!(kth_largest_total(&totals, 6), None);
+        assert_eq!(kth_largest_total(&totals, 0), None);
+    }
+
+    #[test]
+    fn test_elves_above() {
+        let totals = totals(&load_calories(EXAMPLE));
+
+        // the fifth Elf carries exactly 10000, which isn't more
+        assert_eq!(elves_above(&totals, 10000), vec![(2, 11000), (3, 24000)]);
+        assert_eq!(elves_above(&totals, 24000), vec![]);
+    }
+
+    #[test]
+    fn test_merge_elves() {
+        let elves = load_calories(EXAMPLE);
+
+        let merged = merge_elves(&elves, 4, 1).unwrap();
+
+        assert_eq!(
+            merged,
+            vec![
+                vec![1000, 2000, 3000],
+                vec![10000, 4000],
+                vec![5000, 6000],
+                vec![7000, 8000, 9000],
+            ]
+        );
+        // the pooled Elf now carries the second most
+        assert_eq!(sum_top_k(&totals_heap(&merged), 2), 38000);
+        assert_eq!(merge_elves(&elves, 0, 5), Err(MergeError::NoElf(5)));
+        assert_eq!(merge_elves(&elves, 2, 2), Err(MergeError::SameElf(2)));
+    }
+
+    #[test]
+    fn test_elves_needed_for() {
+        let totals = totals(&load_calories(EXAMPLE));
+
+        // 24000 + 11000 falls short, 24000 + 11000 + 10000 doesn't
+        assert_eq!(elves_needed_for(&totals, 40000), Some(3));
+        assert_eq!(elves_needed_for(&totals, 45000), Some(3));
+        assert_eq!(elves_needed_for(&totals, 0), Some(0));
+        assert_eq!(elves_needed_for(&totals, 55001), None);
+    }
+
+    #[test]
+    fn test_leaderboard() {
+        let ranked = leaderboard(&load_calories(EXAMPLE));
+
+        // the fourth Elf carries the most
+        assert_eq!(ranked[0], (1, 3, 24000));
+        assert_eq!(
+            ranked,
+            vec![
+                (1, 3, 24000),
+                (2, 2, 11000),
+                (3, 4, 10000),
+                (4, 0, 6000),
+                (5, 1, 4000)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_duplicate_elves() {
+        let input = "1000\n2000\n\n3000\n\n1000\n2000\n\n2000\n1000\n\n3000\n\n1000\n2000";
+
+        // the order of the items matters, so the fourth Elf isn't a copy
+        assert_eq!(
+            duplicate_elves(&load_calories(input)),
+            vec![(0, 2), (0, 5), (1, 4), (2, 5)]
+        );
+        assert_eq!(duplicate_elves(&load_calories(EXAMPLE)), vec![]);
+    }
+
+    #[test]
+    fn test_total_std_dev() {
+        let totals = totals(&load_calories(EXAMPLE));
+
+        // the mean is 11000, the variance 48800000
+        assert!((total_std_dev(&totals) - 6985.7).abs() < 0.01);
+        assert_eq!(total_std_dev(&[24000]), 0.0);
+        assert_eq!(total_std_dev(&[]), 0.0);
+    }
+
+    #[test]
+    fn test_histogram() {
+        let totals = totals(&load_calories(EXAMPLE));
+
+        assert_eq!(
+            histogram(&totals, 5000),
+            BTreeMap::from([(0, 1), (5000, 1), (10000, 2), (20000, 1)])
+        );
+        assert_eq!(histogram(&[], 5000), BTreeMap::new());
+    }
+
+    #[test]
+    fn test_largest_item() {
+        // the fifth Elf carries the single largest item
+        assert_eq!(largest_item(&load_calories(EXAMPLE)), Some((4, 0, 10000)));
+        assert_eq!(largest_item(&[vec![3, 7], vec![7]]), Some((0, 1, 7)));
+        assert_eq!(largest_item(&[vec![]]), None);
+    }
+
+    #[test]
+    fn test_sum_top_k() {
+        let heap = totals_heap(&load_calories(EXAMPLE));
+
+        assert_eq!(sum_top_k(&heap, 1), 24000);
+        assert_eq!(sum_top_k(&heap, 3), 45000);
+        // asking again gives the same answers
+        assert_eq!(sum_top_k(&heap, 3), 45000);
+        assert_eq!(sum_top_k(&heap, 1), 24000);
+        assert_eq!(sum_top_k(&heap, 0), 0);
+        assert_eq!(sum_top_k(&heap, 10), 55000);
+        assert_eq!(
+            heap.into_sorted_vec(),
+            vec![4000, 6000, 10000, 11000, 24000]
+        );
+    }
+
+    #[test]
+    fn test_repack() {
+        let items = [4, 8, 1, 4, 2, 1];
+
+        assert_eq!(repack(&items, 10), vec![vec![8, 2], vec![4, 4, 1, 1]]);
+        assert_eq!(repack(&items, 20), vec![vec![8, 4, 4, 2, 1, 1]]);
+        // the 8 doesn't fit anywhere, so it's carried alone
+        assert_eq!(repack(&items, 6), vec![vec![8], vec![4, 2], vec![4, 1, 1]]);
+        assert_eq!(repack(&[], 10), Vec::<Vec<u32>>::new());
+    }
+
+    #[test]
+    fn test_repack_example() {
+        let items: Vec<u32> = load_calories(EXAMPLE).concat();
+
+        let elves = repack(&items, 24000);
+
+        assert_eq!(elves.len(), 3);
+        assert!(totals(&elves).iter().all(|&total| total <= 24000));
+        let mut repacked = elves.concat();
+        repacked.sort_unstable();
+        let mut expected = items;
+        expected.sort_unstable();
+        assert_eq!(repacked, expected);
+    }
+
+    #[test]
+    fn test_run_with_input() {
+        let mut output = Vec::new();
+
+        run_with_input(EXAMPLE, &mut output).unwrap();
+
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("carrying the most is: 24000\n"));
+        assert!(output.contains("top three Elves is: 45000\n"));
+        assert!(output.contains("all the Elves is: 55000\n"));
+    }
+
+    #[test]
+    fn test_grand_total() {
+        assert_eq!(grand_total(&load_calories(EXAMPLE)), 55000);
+        assert_eq!(grand_total(&[]), 0);
+        assert_eq!(
+            grand_total(&[vec![u32::MAX], vec![u32::MAX]]),
+            2 * u64::from(u32::MAX)
+        );
+    }
+
+    #[test]
+    fn test_load_calories_sep() {
+        let input = "1000\n2000\n---\n4000\n---\n---\n5000\n6000\n";
+
+        assert_eq!(
+            load_calories_sep(input, "---"),
+            vec![vec![1000, 2000], vec![4000], vec![5000, 6000]]
+        );
+        assert_eq!(
+            load_calories_sep(EXAMPLE, "\n\n"),
+            split_on_blank_line(EXAMPLE)
+                .into_iter()
+                .map(|elf| elf.lines().map(|food| food.parse().unwrap()).collect())
+                .collect::<Vec<Vec<u32>>>()
+        );
+        assert_eq!(
+            load_calories_sep("1000\r\n\r\n2000\r\n", "\n\n"),
+            vec![vec![1000], vec![2000]]
+        );
+    }
+
+    #[test]
+    fn test_load_calories_whitespace_separator() {
+        let input = "1000\n2000\n   \n4000\n\t\n5000";
+
+        assert_eq!(
+            load_calories(input),
+            vec![vec![1000, 2000], vec![4000], vec![5000]]
+        );
+        assert!(validate(input).is_empty());
+    }
+
+    #[test]
+    fn test_stream_top3() {
+        let mut totals = totals(&load_calories(EXAMPLE));
+        totals.sort_unstable_by(|a, b| b.cmp(a));
+
+        assert_eq!(stream_top3(EXAMPLE), [24000, 11000, 10000]);
+        assert_eq!(stream_top3(EXAMPLE)[..], totals[..3]);
+        assert_eq!(stream_top3("100\n\n200"), [200, 100, 0]);
+
+        // indented and invalid lines are skipped, and a blank line may hold spaces or tabs
+        for input in [
+            "1\n 5\n\n3",
+            "1\n2\n \t\n3\n\nfour",
+            "7\r\n8\r\n\r\n9\r",
+            "x\n\n\n4\n5 ",
+        ] {
+            let mut expected = super::totals(&load_calories(input));
+            expected.sort_unstable_by(|a, b| b.cmp(a));
+            expected.resize(3, 0);
+            assert_eq!(stream_top3(input)[..], expected[..], "{input:?}");
+        }
+        assert_eq!(stream_top3("1\n 5\n\n3"), [3, 1, 0]);
+
+        assert_eq!(stream_top3("4294967295\n1\n\n2"), [u32::MAX, 2, 0]);
+    }
 }
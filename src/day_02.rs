@@ -68,39 +68,97 @@
 ///
 /// Following the Elf's instructions for the second column, what would your total score be if
 /// everything goes exactly according to your strategy guide?
+use crate::DayResult;
 use itertools::Itertools;
+use std::cmp::Ordering;
 use std::collections::HashMap;
+use std::io::{self, Write};
 
-const INPUT: &str = include_str!("../input/day_02");
+#[cfg(feature = "bundled-input")]
+pub const INPUT: &str = include_str!("../input/day_02");
 
+#[cfg(feature = "bundled-input")]
 pub fn run() {
-    let guide = load_guide(INPUT);
+    run_with_input(INPUT, &mut io::stdout()).expect("Couldn't write the answers");
+}
 
-    let strategy = translate_guide(&guide);
+pub fn run_with_input(input: &str, out: &mut dyn Write) -> io::Result<()> {
+    let result = solve(input);
 
-    let score = score_strategy(&strategy);
-    println!(
+    writeln!(
+        out,
         "The total score according to the strategy guide is: {}",
-        score
-    );
+        result.part_one
+    )?;
 
-    let new_strategy = decrypt_guide(&guide);
-
-    let new_score = score_strategy(&new_strategy);
-    println!(
+    writeln!(
+        out,
         "The total score using the new instructions according to the strategy guide is: {}",
-        new_score
-    );
+        result.part_two
+    )?;
+    Ok(())
+}
+
+pub fn solve(input: &str) -> DayResult {
+    let guide = load_guide(input);
+
+    let shape_score = score(&guide, Interpretation::ShapeColumn)
+        .expect("The guide should only contain A-C and X-Z");
+    let outcome_score = score(&guide, Interpretation::OutcomeColumn)
+        .expect("The guide should only contain A-C and X-Z");
+
+    DayResult::new(shape_score, outcome_score)
 }
 
+/// How to read the second column of the strategy guide.
 #[derive(Debug, PartialEq, Copy, Clone)]
-enum Sign {
+pub enum Interpretation {
+    /// The sign you should play, as assumed in part one.
+    ShapeColumn,
+    /// How the round needs to end, as the Elf explains in part two.
+    OutcomeColumn,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum GuideError {
+    UnexpectedCharacter(char),
+    /// A line that isn't two single character columns, with its line number.
+    InvalidLine(usize, String),
+    /// A packed guide with a character left over, with the amount of characters.
+    OddLength(usize),
+}
+
+/// The total score of following the guide, reading it with the given interpretation.
+pub fn score(guide: &[(char, char)], interpretation: Interpretation) -> Result<u32, GuideError> {
+    let strategy = match interpretation {
+        Interpretation::ShapeColumn => translate_guide(guide)?,
+        Interpretation::OutcomeColumn => decrypt_guide(guide)?,
+    };
+    Ok(score_strategy(&strategy))
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
+pub enum Sign {
     Rock,
     Paper,
     Scissors,
 }
 
 impl Sign {
+    pub fn all() -> [Sign; 3] {
+        [Sign::Rock, Sign::Paper, Sign::Scissors]
+    }
+
+    /// The position of the sign in `Sign::all()`.
+    pub fn index(&self) -> usize {
+        *self as usize
+    }
+
+    /// The points for playing the sign: 1 for Rock, 2 for Paper and 3 for Scissors.
+    pub fn score(&self) -> u32 {
+        self.index() as u32 + 1
+    }
+
     fn loses_to(&self) -> Sign {
         match self {
             Sign::Rock => Sign::Paper,
@@ -118,21 +176,63 @@ impl Sign {
     }
 }
 
-fn translate_guide(guide: &Vec<(char, char)>) -> Vec<(Sign, Sign)> {
-    let mut key = HashMap::new();
-    key.insert('A', Sign::Rock);
-    key.insert('B', Sign::Paper);
-    key.insert('C', Sign::Scissors);
-    key.insert('X', Sign::Rock);
-    key.insert('Y', Sign::Paper);
-    key.insert('Z', Sign::Scissors);
+/// How a round ends, seen from your side.
+#[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
+pub enum Outcome {
+    Lose,
+    Draw,
+    Win,
+}
+
+impl Outcome {
+    /// The points for how the round ends: 0 for a loss, 3 for a draw and 6 for a win.
+    pub fn score(&self) -> u32 {
+        match self {
+            Outcome::Lose => 0,
+            Outcome::Draw => 3,
+            Outcome::Win => 6,
+        }
+    }
+}
+
+/// The total score of a strategy split into the points for the signs and for how the rounds end.
+#[derive(Debug, PartialEq)]
+pub struct ScoreBreakdown {
+    pub shape_points: u32,
+    pub outcome_points: u32,
+}
+
+impl TryFrom<char> for Sign {
+    type Error = GuideError;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        match c {
+            'A' | 'X' => Ok(Sign::Rock),
+            'B' | 'Y' => Ok(Sign::Paper),
+            'C' | 'Z' => Ok(Sign::Scissors),
+            c => Err(GuideError::UnexpectedCharacter(c)),
+        }
+    }
+}
+
+fn translate_guide(guide: &[(char, char)]) -> Result<Vec<(Sign, Sign)>, GuideError> {
     guide
         .iter()
-        .map(|(opponent, own)| (*key.get(opponent).unwrap(), *key.get(own).unwrap()))
+        .map(|(opponent, own)| Ok((Sign::try_from(*opponent)?, Sign::try_from(*own)?)))
         .collect()
 }
 
-fn decrypt_guide(guide: &Vec<(char, char)>) -> Vec<(Sign, Sign)> {
+fn decrypt_guide(guide: &[(char, char)]) -> Result<Vec<(Sign, Sign)>, GuideError> {
+    // there are only nine combinations of the opponent's sign and the outcome, so the sign to play
+    // is worked out once for each of them rather than for every round
+    let own_signs = Sign::all().map(|opponent_sign| {
+        [Outcome::Lose, Outcome::Draw, Outcome::Win].map(|outcome| match outcome {
+            Outcome::Lose => opponent_sign.wins_from(),
+            Outcome::Draw => opponent_sign,
+            Outcome::Win => opponent_sign.loses_to(),
+        })
+    });
+
     guide
         .iter()
         .map(|(opponent, own)| {
@@ -140,18 +240,94 @@ fn decrypt_guide(guide: &Vec<(char, char)>) -> Vec<(Sign, Sign)> {
                 'A' => Sign::Rock,
                 'B' => Sign::Paper,
                 'C' => Sign::Scissors,
-                c => panic!("Got an unexpected character: '{}'", c),
+                c => return Err(GuideError::UnexpectedCharacter(*c)),
             };
-            let own_sign = match own {
-                // X -> lose
-                'X' => opponent_sign.wins_from(),
-                // Y -> draw
-                'Y' => opponent_sign,
-                // Z -> win
-                'Z' => opponent_sign.loses_to(),
-                c => panic!("Got an unexpected character: '{}'", c),
+            let outcome = match own {
+                'X' => Outcome::Lose,
+                'Y' => Outcome::Draw,
+                'Z' => Outcome::Win,
+                c => return Err(GuideError::UnexpectedCharacter(*c)),
             };
-            (opponent_sign, own_sign)
+            Ok((
+                opponent_sign,
+                own_signs[opponent_sign.index()][outcome as usize],
+            ))
+        })
+        .collect()
+}
+
+/// Checks that every line holds two columns of a single character, from `A-C` in the first column
+/// and from `X-Z` in the second one.
+pub fn validate(input: &str) -> Vec<String> {
+    input
+        .lines()
+        .enumerate()
+        .filter_map(|(i, line)| match load_guide_strict(line) {
+            Ok(guide) if guide.len() == 1 => None,
+            Err(GuideError::UnexpectedCharacter(c)) => Some(format!(
+                "line {}: '{}' has '{}', which isn't one of A-C or X-Z",
+                i + 1,
+                line,
+                c
+            )),
+            _ => Some(format!(
+                "line {}: '{}' isn't two single character columns",
+                i + 1,
+                line
+            )),
+        })
+        .collect()
+}
+
+/// Like `load_guide`, but every line should be two single character columns, the first one from
+/// `A-C` and the second one from `X-Z`, instead of taking the first character of whatever is there.
+pub fn load_guide_strict(input: &str) -> Result<Vec<(char, char)>, GuideError> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| {
+            let (opponent, own) = line
+                .split_whitespace()
+                .map(|column| column.chars().exactly_one().ok())
+                .collect_tuple()
+                .and_then(|(opponent, own)| Some((opponent?, own?)))
+                .ok_or_else(|| GuideError::InvalidLine(i + 1, line.to_string()))?;
+            match (opponent, own) {
+                ('A'..='C', 'X'..='Z') => Ok((opponent, own)),
+                ('A'..='C', c) | (c, _) => Err(GuideError::UnexpectedCharacter(c)),
+            }
+        })
+        .collect()
+}
+
+/// Reads a guide that's packed into a single string like `AYBXCZ`, taking the characters two at a
+/// time. Surrounding whitespace, like a trailing newline, is ignored.
+pub fn load_guide_packed(input: &str) -> Result<Vec<(char, char)>, GuideError> {
+    let characters: Vec<char> = input.trim().chars().collect();
+    if !characters.len().is_multiple_of(2) {
+        return Err(GuideError::OddLength(characters.len()));
+    }
+    Ok(characters
+        .chunks(2)
+        .map(|pair| (pair[0], pair[1]))
+        .collect())
+}
+
+/// A guide of `rounds` rounds with valid characters, the same for the same `seed` every time. It
+/// uses a simple linear congruential generator, so it's fine for tests but nothing else.
+pub fn random_guide(rounds: usize, seed: u64) -> Vec<(char, char)> {
+    let mut state = seed;
+    let mut next = |n: u64| {
+        state = state
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        ((state >> 33) % n) as u8
+    };
+    (0..rounds)
+        .map(|_| {
+            let opponent = (b'A' + next(3)) as char;
+            let own = (b'X' + next(3)) as char;
+            (opponent, own)
         })
         .collect()
 }
@@ -159,7 +335,7 @@ fn decrypt_guide(guide: &Vec<(char, char)>) -> Vec<(Sign, Sign)> {
 fn load_guide(input: &str) -> Vec<(char, char)> {
     input
         .lines()
-        .map(|line| {
+        .filter_map(|line| {
             line.split_whitespace()
                 .map(|character| {
                     character
@@ -169,33 +345,218 @@ fn load_guide(input: &str) -> Vec<(char, char)> {
                 })
                 .collect_tuple()
         })
-        .flatten()
         .collect()
 }
 
-fn score_strategy(strategy: &Vec<(Sign, Sign)>) -> u32 {
+fn score_strategy(strategy: &[(Sign, Sign)]) -> u32 {
     strategy.iter().map(round_score).sum()
 }
 
-fn round_score((opponent_sign, own_sign): &(Sign, Sign)) -> u32 {
-    // first calculate score for the own sign
-    let mut score = match own_sign {
-        Sign::Rock => 1,
-        Sign::Paper => 2,
-        Sign::Scissors => 3,
-    };
+/// The score of a single round given as in the guide, reading the second column as your sign.
+pub fn score_raw(opponent: char, own: char) -> Result<u32, GuideError> {
+    Ok(round_score(&(
+        Sign::try_from(opponent)?,
+        Sign::try_from(own)?,
+    )))
+}
 
-    // if it's draw add 3
-    if opponent_sign == own_sign {
-        score += 3;
+/// For each of your signs, the sign the opponent has to play for the round to end in the desired
+/// outcome.
+pub fn opponent_for_outcome(own: &[Sign], desired: Outcome) -> Vec<Sign> {
+    own.iter()
+        .map(|own_sign| match desired {
+            Outcome::Lose => own_sign.loses_to(),
+            Outcome::Draw => *own_sign,
+            Outcome::Win => own_sign.wins_from(),
+        })
+        .collect()
+}
+
+/// How the round ends for you.
+pub fn outcome((opponent_sign, own_sign): &(Sign, Sign)) -> Outcome {
+    if own_sign == opponent_sign {
+        Outcome::Draw
+    } else if *own_sign == opponent_sign.loses_to() {
+        Outcome::Win
+    } else {
+        Outcome::Lose
+    }
+}
+
+/// The most rounds in a row you win following the strategy.
+pub fn longest_win_streak(strategy: &[(Sign, Sign)]) -> usize {
+    strategy
+        .iter()
+        .scan(0, |streak, round| {
+            *streak = match outcome(round) {
+                Outcome::Win => *streak + 1,
+                Outcome::Lose | Outcome::Draw => 0,
+            };
+            Some(*streak)
+        })
+        .max()
+        .unwrap_or(0)
+}
+
+/// For every sign of the opponent, the round with the sign that scores the most. That's always the
+/// sign that wins: even the lowest score for a win (7, Rock against Scissors) beats the highest
+/// score for a draw (6, Scissors against Scissors).
+pub fn best_response_strategy(opponents: &[Sign]) -> Vec<(Sign, Sign)> {
+    opponents
+        .iter()
+        .map(|&opponent_sign| {
+            Sign::all()
+                .into_iter()
+                .map(|own_sign| (opponent_sign, own_sign))
+                .max_by_key(round_score)
+                .expect("There should be signs to choose from")
+        })
+        .collect()
+}
+
+/// The highest total score possible against the opponent's signs.
+pub fn optimal_score(opponents: &[Sign]) -> u32 {
+    score_strategy(&best_response_strategy(opponents))
+}
+
+/// The lowest total score possible with your signs, when the opponent knows them and beats every
+/// one. That leaves only the points for your shapes.
+pub fn worst_case_score(own: &[Sign]) -> u32 {
+    let strategy: Vec<(Sign, Sign)> = own
+        .iter()
+        .map(|own_sign| (own_sign.loses_to(), *own_sign))
+        .collect();
+    score_strategy(&strategy)
+}
+
+/// The total score after each round of the strategy.
+pub fn cumulative_scores(strategy: &[(Sign, Sign)]) -> Vec<u32> {
+    strategy
+        .iter()
+        .scan(0, |total, round| {
+            *total += round_score(round);
+            Some(*total)
+        })
+        .collect()
+}
+
+/// The index, starting at 0, and score of the round that scores the most, the first one on a tie.
+pub fn best_round(strategy: &[(Sign, Sign)]) -> Option<(usize, u32)> {
+    strategy
+        .iter()
+        .map(round_score)
+        .enumerate()
+        .fold(None, |best, candidate| match best {
+            Some((_, score)) if score >= candidate.1 => best,
+            _ => Some(candidate),
+        })
+}
+
+/// For every round, how many more points it scores when the guide is read like in part two than
+/// when it's read like in part one.
+pub fn interpretation_deltas(guide: &[(char, char)]) -> Result<Vec<i32>, GuideError> {
+    let part_one = translate_guide(guide)?;
+    let part_two = decrypt_guide(guide)?;
+    Ok(part_one
+        .iter()
+        .zip(&part_two)
+        .map(|(one, two)| round_score(two) as i32 - round_score(one) as i32)
+        .collect())
+}
+
+/// The sign that scores the most when you play it every round against the opponent's signs, with
+/// that score. On a tie it's the first one in `Sign::all()`.
+pub fn best_fixed_shape(opponents: &[Sign]) -> (Sign, u32) {
+    Sign::all()
+        .into_iter()
+        .map(|own_sign| {
+            let total = opponents
+                .iter()
+                .map(|&opponent_sign| round_score(&(opponent_sign, own_sign)))
+                .sum();
+            (own_sign, total)
+        })
+        .reduce(|best, candidate| {
+            if candidate.1 > best.1 {
+                candidate
+            } else {
+                best
+            }
+        })
+        .expect("There should be signs to choose from")
+}
+
+/// Splits the total score of a strategy into the score for the signs you play, 1 for Rock, 2 for
+/// Paper and 3 for Scissors, and the score for how the rounds end, 0 for a loss, 3 for a draw and 6
+/// for a win. Together they add up to the score of the strategy.
+pub fn score_breakdown(strategy: &[(Sign, Sign)]) -> ScoreBreakdown {
+    ScoreBreakdown {
+        shape_points: strategy.iter().map(|(_, own_sign)| own_sign.score()).sum(),
+        outcome_points: strategy.iter().map(|round| outcome(round).score()).sum(),
     }
+}
+
+/// Compares the total scores of two strategies, `Greater` meaning `a` scores higher.
+pub fn better_strategy(a: &[(Sign, Sign)], b: &[(Sign, Sign)]) -> Ordering {
+    score_strategy(a).cmp(&score_strategy(b))
+}
 
-    // if it's a victory add 6
-    if &opponent_sign.loses_to() == own_sign {
-        score += 6;
+/// Counts how often each sign is played, by the opponent and by yourself.
+pub fn shape_counts(strategy: &[(Sign, Sign)]) -> (HashMap<Sign, usize>, HashMap<Sign, usize>) {
+    let mut opponent_counts = HashMap::new();
+    let mut own_counts = HashMap::new();
+    for (opponent_sign, own_sign) in strategy {
+        *opponent_counts.entry(*opponent_sign).or_insert(0) += 1;
+        *own_counts.entry(*own_sign).or_insert(0) += 1;
     }
+    (opponent_counts, own_counts)
+}
+
+/// Counts how often each outcome occurs, leaving out the ones that never do.
+pub fn outcome_counts(strategy: &[(Sign, Sign)]) -> HashMap<Outcome, usize> {
+    let mut counts = HashMap::new();
+    for round in strategy {
+        *counts.entry(outcome(round)).or_insert(0) += 1;
+    }
+    counts
+}
 
-    score
+/// Whether the strategy wins, loses and draws at least one round each.
+pub fn is_balanced(strategy: &[(Sign, Sign)]) -> bool {
+    outcome_counts(strategy).len() == 3
+}
+
+/// Plays a round-robin where every player always throws the same sign, returning the total score
+/// of each player over the rounds against all the other players.
+pub fn round_robin(players: &[Sign]) -> Vec<u32> {
+    players
+        .iter()
+        .enumerate()
+        .map(|(i, own_sign)| {
+            players
+                .iter()
+                .enumerate()
+                .filter(|(j, _)| *j != i)
+                .map(|(_, opponent_sign)| round_score(&(*opponent_sign, *own_sign)))
+                .sum()
+        })
+        .collect()
+}
+
+/// The score of every round, indexed by the opponent's sign and then your own sign: the score for
+/// your sign (1 for Rock, 2 for Paper and 3 for Scissors) plus 0 for a loss, 3 for a draw and 6
+/// for a win.
+const ROUND_SCORES: [[u32; 3]; 3] = [
+    // the opponent plays Rock
+    [4, 8, 3],
+    // the opponent plays Paper
+    [1, 5, 9],
+    // the opponent plays Scissors
+    [7, 2, 6],
+];
+
+fn round_score((opponent_sign, own_sign): &(Sign, Sign)) -> u32 {
+    ROUND_SCORES[opponent_sign.index()][own_sign.index()]
 }
 
 #[cfg(test)]
@@ -224,7 +585,7 @@ mod tests {
             (Sign::Scissors, Sign::Rock),
         ];
 
-        assert_eq!(decrypt_guide(&input), expected);
+        assert_eq!(decrypt_guide(&input), Ok(expected));
     }
 
     #[test]
@@ -259,4 +620,391 @@ mod tests {
         // 3 + 3 = 6.
         assert_eq!(round_score(&(Sign::Scissors, Sign::Scissors)), 6);
     }
+
+    #[test]
+    fn test_round_robin() {
+        // Rock: loses to Paper (1 + 0), wins from Scissors (1 + 6)
+        // Paper: wins from Rock (2 + 6), loses to Scissors (2 + 0)
+        // Scissors: wins from Paper (3 + 6), loses to Rock (3 + 0)
+        let players = [Sign::Rock, Sign::Paper, Sign::Scissors];
+
+        assert_eq!(round_robin(&players), vec![8, 10, 12]);
+    }
+
+    #[test]
+    fn test_round_robin_draws() {
+        // two Rocks draw (1 + 3) and both beat the Scissors (1 + 6)
+        let players = [Sign::Rock, Sign::Rock, Sign::Scissors];
+
+        assert_eq!(round_robin(&players), vec![11, 11, 6]);
+    }
+
+    #[test]
+    fn test_sign_all() {
+        assert_eq!(Sign::all().len(), 3);
+
+        for sign in Sign::all() {
+            // going around the cycle of three signs ends up at the start
+            assert_eq!(sign.loses_to().loses_to().loses_to(), sign);
+            assert_eq!(sign.wins_from().wins_from().wins_from(), sign);
+            assert_eq!(sign.loses_to().wins_from(), sign);
+            assert_ne!(sign.loses_to(), sign);
+        }
+    }
+
+    #[test]
+    fn test_shape_counts() {
+        // A Y
+        // B X
+        // C Z
+        let strategy = vec![
+            (Sign::Rock, Sign::Paper),
+            (Sign::Paper, Sign::Rock),
+            (Sign::Scissors, Sign::Scissors),
+        ];
+
+        let (opponent_counts, own_counts) = shape_counts(&strategy);
+
+        for sign in Sign::all() {
+            assert_eq!(opponent_counts.get(&sign), Some(&1));
+            assert_eq!(own_counts.get(&sign), Some(&1));
+        }
+    }
+
+    #[test]
+    fn test_shape_counts_all_rock() {
+        let strategy = vec![(Sign::Paper, Sign::Rock), (Sign::Scissors, Sign::Rock)];
+
+        let (opponent_counts, own_counts) = shape_counts(&strategy);
+
+        assert_eq!(opponent_counts.get(&Sign::Rock), None);
+        assert_eq!(opponent_counts.get(&Sign::Paper), Some(&1));
+        assert_eq!(own_counts.get(&Sign::Rock), Some(&2));
+        assert_eq!(own_counts.len(), 1);
+    }
+
+    #[test]
+    fn test_score() {
+        let guide = vec![('A', 'Y'), ('B', 'X'), ('C', 'Z')];
+
+        assert_eq!(score(&guide, Interpretation::ShapeColumn), Ok(15));
+        assert_eq!(score(&guide, Interpretation::OutcomeColumn), Ok(12));
+    }
+
+    #[test]
+    fn test_score_unexpected_character() {
+        let guide = vec![('A', 'Y'), ('D', 'X')];
+
+        assert_eq!(
+            score(&guide, Interpretation::ShapeColumn),
+            Err(GuideError::UnexpectedCharacter('D'))
+        );
+        assert_eq!(
+            score(&guide, Interpretation::OutcomeColumn),
+            Err(GuideError::UnexpectedCharacter('D'))
+        );
+    }
+
+    #[test]
+    fn test_better_strategy() {
+        let guide = vec![('A', 'Y'), ('B', 'X'), ('C', 'Z')];
+        let shapes = translate_guide(&guide).unwrap();
+        let outcomes = decrypt_guide(&guide).unwrap();
+
+        // 15 against 12
+        assert_eq!(better_strategy(&shapes, &outcomes), Ordering::Greater);
+        assert_eq!(better_strategy(&outcomes, &shapes), Ordering::Less);
+        assert_eq!(better_strategy(&shapes, &shapes), Ordering::Equal);
+    }
+
+    #[test]
+    fn test_opponent_for_outcome() {
+        assert_eq!(
+            opponent_for_outcome(&[Sign::Rock], Outcome::Win),
+            vec![Sign::Scissors]
+        );
+        assert_eq!(
+            opponent_for_outcome(&Sign::all(), Outcome::Lose),
+            vec![Sign::Paper, Sign::Scissors, Sign::Rock]
+        );
+        assert_eq!(
+            opponent_for_outcome(&Sign::all(), Outcome::Draw),
+            Sign::all()
+        );
+    }
+
+    #[test]
+    fn test_load_guide_strict() {
+        assert_eq!(
+            load_guide_strict("A Y\nB X\nC Z"),
+            Ok(vec![('A', 'Y'), ('B', 'X'), ('C', 'Z')])
+        );
+        // the lenient parser would read this as 'A'
+        assert_eq!(
+            load_guide_strict("A Y\nAB Y"),
+            Err(GuideError::InvalidLine(2, "AB Y".to_string()))
+        );
+        assert_eq!(
+            load_guide_strict("A Y\nB"),
+            Err(GuideError::InvalidLine(2, "B".to_string()))
+        );
+        assert_eq!(
+            load_guide_strict("A Y\nD X"),
+            Err(GuideError::UnexpectedCharacter('D'))
+        );
+        assert_eq!(
+            load_guide_strict("A Y\nB C"),
+            Err(GuideError::UnexpectedCharacter('C'))
+        );
+    }
+
+    #[test]
+    fn test_load_guide_packed() {
+        let guide = load_guide_packed("AYBXCZ\n").unwrap();
+
+        assert_eq!(guide, vec![('A', 'Y'), ('B', 'X'), ('C', 'Z')]);
+        assert_eq!(guide, load_guide("A Y\nB X\nC Z"));
+        assert_eq!(load_guide_packed("AYB"), Err(GuideError::OddLength(3)));
+        assert_eq!(load_guide_packed(""), Ok(vec![]));
+    }
+
+    // how the score of a round was calculated before the lookup table
+    fn computed_round_score((opponent_sign, own_sign): &(Sign, Sign)) -> u32 {
+        // first calculate score for the own sign
+        let mut score = match own_sign {
+            Sign::Rock => 1,
+            Sign::Paper => 2,
+            Sign::Scissors => 3,
+        };
+
+        // if it's draw add 3
+        if opponent_sign == own_sign {
+            score += 3;
+        }
+
+        // if it's a victory add 6
+        if &opponent_sign.loses_to() == own_sign {
+            score += 6;
+        }
+
+        score
+    }
+
+    #[test]
+    fn test_round_score_table() {
+        for opponent_sign in Sign::all() {
+            for own_sign in Sign::all() {
+                let round = (opponent_sign, own_sign);
+                assert_eq!(
+                    round_score(&round),
+                    computed_round_score(&round),
+                    "{:?}",
+                    round
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_sign_index() {
+        for (i, sign) in Sign::all().iter().enumerate() {
+            assert_eq!(sign.index(), i);
+        }
+    }
+
+    #[test]
+    fn test_score_raw() {
+        // "In the first round, your opponent will choose Rock (A), and you should choose Paper (Y).
+        // This ends in a win for you with a score of 8 (2 because you chose Paper + 6 because you
+        // won)."
+        assert_eq!(score_raw('A', 'Y'), Ok(8));
+        assert_eq!(score_raw('B', 'X'), Ok(1));
+        assert_eq!(score_raw('C', 'Z'), Ok(6));
+        assert_eq!(
+            score_raw('A', 'W'),
+            Err(GuideError::UnexpectedCharacter('W'))
+        );
+    }
+
+    // how the guide was decrypted before the lookup table
+    fn computed_decrypt_guide(guide: &[(char, char)]) -> Result<Vec<(Sign, Sign)>, GuideError> {
+        guide
+            .iter()
+            .map(|(opponent, own)| {
+                let opponent_sign = match opponent {
+                    'A' => Sign::Rock,
+                    'B' => Sign::Paper,
+                    'C' => Sign::Scissors,
+                    c => return Err(GuideError::UnexpectedCharacter(*c)),
+                };
+                let own_sign = match own {
+                    // X -> lose
+                    'X' => opponent_sign.wins_from(),
+                    // Y -> draw
+                    'Y' => opponent_sign,
+                    // Z -> win
+                    'Z' => opponent_sign.loses_to(),
+                    c => return Err(GuideError::UnexpectedCharacter(*c)),
+                };
+                Ok((opponent_sign, own_sign))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_decrypt_guide_table() {
+        let guide = random_guide(10_000, 2022);
+
+        assert_eq!(decrypt_guide(&guide), computed_decrypt_guide(&guide));
+        assert_eq!(
+            decrypt_guide(&[('A', 'Y'), ('D', 'X')]),
+            computed_decrypt_guide(&[('A', 'Y'), ('D', 'X')])
+        );
+    }
+
+    #[test]
+    fn test_random_guide() {
+        let guide = random_guide(1000, 42);
+
+        assert_eq!(guide.len(), 1000);
+        assert_eq!(guide, random_guide(1000, 42));
+        assert_ne!(guide, random_guide(1000, 43));
+        assert!(guide
+            .iter()
+            .all(|(opponent, own)| ('A'..='C').contains(opponent) && ('X'..='Z').contains(own)));
+        assert!(score(&guide, Interpretation::OutcomeColumn).is_ok());
+        assert!(random_guide(0, 42).is_empty());
+    }
+
+    #[test]
+    fn test_outcome() {
+        assert_eq!(outcome(&(Sign::Rock, Sign::Paper)), Outcome::Win);
+        assert_eq!(outcome(&(Sign::Paper, Sign::Rock)), Outcome::Lose);
+        assert_eq!(outcome(&(Sign::Scissors, Sign::Scissors)), Outcome::Draw);
+    }
+
+    #[test]
+    fn test_longest_win_streak() {
+        let win = (Sign::Rock, Sign::Paper);
+        let draw = (Sign::Rock, Sign::Rock);
+        let lose = (Sign::Rock, Sign::Scissors);
+
+        let strategy = [win, win, draw, win, win, win, lose, win];
+        assert_eq!(longest_win_streak(&strategy), 3);
+        assert_eq!(longest_win_streak(&[draw, lose]), 0);
+        assert_eq!(longest_win_streak(&[]), 0);
+    }
+
+    #[test]
+    fn test_worst_case_score() {
+        let strategy = translate_guide(&load_guide("A Y\nB X\nC Z")).unwrap();
+        let own: Vec<Sign> = strategy.iter().map(|(_, own_sign)| *own_sign).collect();
+
+        // Paper, Rock and Scissors lose every round
+        assert_eq!(worst_case_score(&own), 2 + 1 + 3);
+        assert_eq!(worst_case_score(&[]), 0);
+    }
+
+    #[test]
+    fn test_cumulative_scores() {
+        let strategy = translate_guide(&load_guide("A Y\nB X\nC Z")).unwrap();
+
+        assert_eq!(cumulative_scores(&strategy), vec![8, 9, 15]);
+        assert!(cumulative_scores(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_best_round() {
+        let strategy = translate_guide(&load_guide("A Y\nB X\nC Z")).unwrap();
+
+        // Paper against Rock scores 2 + 6
+        assert_eq!(best_round(&strategy), Some((0, 8)));
+        assert_eq!(
+            best_round(&[(Sign::Rock, Sign::Rock), (Sign::Paper, Sign::Paper)]),
+            Some((1, 5))
+        );
+        assert_eq!(best_round(&[]), None);
+    }
+
+    #[test]
+    fn test_interpretation_deltas() {
+        let guide = load_guide("A Y\nB X\nC Z");
+
+        // 8, 1 and 6 points in part one, 4, 1 and 7 in part two
+        assert_eq!(interpretation_deltas(&guide), Ok(vec![-4, 0, 1]));
+        assert_eq!(
+            interpretation_deltas(&[('A', 'W')]),
+            Err(GuideError::UnexpectedCharacter('W'))
+        );
+    }
+
+    #[test]
+    fn test_best_fixed_shape() {
+        let strategy = translate_guide(&load_guide("A Y\nB X\nC Z")).unwrap();
+        let opponents: Vec<Sign> = strategy
+            .iter()
+            .map(|(opponent_sign, _)| *opponent_sign)
+            .collect();
+
+        // Rock scores 12 and Paper 15
+        assert_eq!(best_fixed_shape(&opponents), (Sign::Scissors, 18));
+        assert_eq!(best_fixed_shape(&[]), (Sign::Rock, 0));
+    }
+
+    #[test]
+    fn test_score_breakdown() {
+        let strategy = translate_guide(&load_guide("A Y\nB X\nC Z")).unwrap();
+
+        let breakdown = score_breakdown(&strategy);
+
+        // Paper, Rock and Scissors for 2 + 1 + 3, and a win, a loss and a draw for 6 + 0 + 3
+        assert_eq!(
+            breakdown,
+            ScoreBreakdown {
+                shape_points: 6,
+                outcome_points: 9,
+            }
+        );
+        assert_eq!(breakdown.shape_points + breakdown.outcome_points, 15);
+        for round in Sign::all().into_iter().cartesian_product(Sign::all()) {
+            assert_eq!(
+                round.1.score() + outcome(&round).score(),
+                round_score(&round)
+            );
+        }
+    }
+
+    #[test]
+    fn test_is_balanced() {
+        let win = (Sign::Rock, Sign::Paper);
+        let draw = (Sign::Rock, Sign::Rock);
+        let lose = (Sign::Rock, Sign::Scissors);
+
+        assert!(is_balanced(&[win, draw, draw, lose]));
+        assert_eq!(
+            outcome_counts(&[win, draw, draw, lose]),
+            HashMap::from([(Outcome::Win, 1), (Outcome::Draw, 2), (Outcome::Lose, 1)])
+        );
+        assert!(!is_balanced(&[draw, draw, draw]));
+        assert!(!is_balanced(&[]));
+    }
+
+    #[test]
+    fn test_best_response_strategy() {
+        let opponents = [Sign::Rock, Sign::Paper, Sign::Scissors];
+
+        let strategy = best_response_strategy(&opponents);
+
+        assert_eq!(
+            strategy,
+            vec![
+                (Sign::Rock, Sign::Paper),
+                (Sign::Paper, Sign::Scissors),
+                (Sign::Scissors, Sign::Rock)
+            ]
+        );
+        assert!(strategy.iter().all(|round| outcome(round) == Outcome::Win));
+        // 8 + 9 + 7
+        assert_eq!(optimal_score(&opponents), 24);
+    }
 }
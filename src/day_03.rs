@@ -88,38 +88,103 @@
 ///
 /// Find the item type that corresponds to the badges of each three-Elf group. What is the sum of
 /// the priorities of those item types?
+use crate::DayResult;
+use itertools::Itertools;
 use std::collections::HashSet;
+use std::io::{self, Write};
 
-const INPUT: &str = include_str!("../input/day_03");
+#[cfg(feature = "bundled-input")]
+pub const INPUT: &str = include_str!("../input/day_03");
 
+#[cfg(feature = "bundled-input")]
 pub fn run() {
-    let rucksacks = load_rucksacks(INPUT);
+    run_with_input(INPUT, &mut io::stdout()).expect("Couldn't write the answers");
+}
+
+pub fn run_with_input(input: &str, out: &mut dyn Write) -> io::Result<()> {
+    let result = solve(input);
+
+    writeln!(
+        out,
+        "The sum of the priorities of the item types found in both compartments is: {}",
+        result.part_one
+    )?;
+
+    writeln!(
+        out,
+        "The sum of the priorities of the badges of each three-Elf group is: {}",
+        result.part_two
+    )?;
+    Ok(())
+}
+
+pub fn solve(input: &str) -> DayResult {
+    let rucksacks = load_rucksacks(input);
 
     let total_priorities: u32 = rucksacks
         .iter()
+        .map(Vec::as_slice)
         .map(find_duplicate_item)
         .map(convert_to_priority)
         .sum();
 
-    println!(
-        "The sum of the priorities of the item types found in both compartments is: {}",
-        total_priorities
-    );
-
     let badge_priorities: u32 = rucksacks
         .chunks(3)
         .map(find_badge)
         .map(convert_to_priority)
         .sum();
 
-    println!(
-        "The sum of the priorities of the badges of each three-Elf group is: {}",
-        badge_priorities
-    );
+    DayResult::new(total_priorities, badge_priorities)
 }
 
 type Item = char;
 
+/// Checks that every rucksack holds an even amount of items, all of them letters, with an item in
+/// both compartments. When they all do, every group of three rucksacks should share exactly one
+/// item as its badge.
+pub fn validate(input: &str) -> Vec<String> {
+    let problems: Vec<String> = input
+        .lines()
+        .enumerate()
+        .filter_map(|(i, line)| {
+            if line.is_empty()
+                || line.len() % 2 != 0
+                || !line.chars().all(|item| item.is_ascii_alphabetic())
+            {
+                Some(format!(
+                    "line {}: '{}' isn't an even amount of letters",
+                    i + 1,
+                    line
+                ))
+            } else if duplicate_item(&line.chars().collect::<Vec<_>>()).is_none() {
+                Some(format!(
+                    "line {}: '{}' has no item in both compartments",
+                    i + 1,
+                    line
+                ))
+            } else {
+                None
+            }
+        })
+        .collect();
+    if !problems.is_empty() {
+        return problems;
+    }
+
+    load_rucksacks(input)
+        .chunks(3)
+        .enumerate()
+        .filter(|(_, group)| badge(group).is_none())
+        .map(|(i, group)| {
+            format!(
+                "lines {}-{}: the group doesn't share exactly one item",
+                i * 3 + 1,
+                i * 3 + group.len()
+            )
+        })
+        .collect()
+}
+
 fn load_rucksacks(input: &str) -> Vec<Vec<Item>> {
     input.lines().map(|line| line.chars().collect()).collect()
 }
@@ -132,7 +197,11 @@ fn convert_to_priority(item: &Item) -> u32 {
     }
 }
 
-fn find_duplicate_item(rucksack: &Vec<Item>) -> &Item {
+fn find_duplicate_item(rucksack: &[Item]) -> &Item {
+    duplicate_item(rucksack).expect("There should be a duplicate item")
+}
+
+fn duplicate_item(rucksack: &[Item]) -> Option<&Item> {
     let midway = rucksack.len() / 2;
 
     // create a set of the items in the first compartment
@@ -140,23 +209,22 @@ fn find_duplicate_item(rucksack: &Vec<Item>) -> &Item {
     let mut rucksack_iter = rucksack.iter();
     let first_compartment: HashSet<&char> = rucksack_iter.by_ref().take(midway).collect();
 
-    rucksack_iter
-        .find(|item| first_compartment.contains(item))
-        .expect("There should be a duplicate item")
+    rucksack_iter.find(|item| first_compartment.contains(item))
 }
 
 fn find_badge(rucksacks: &[Vec<Item>]) -> &Item {
-    let badge_set: HashSet<&Item> = rucksacks
+    badge(rucksacks).expect("There is not exactly 1 item matching in all rucksacks")
+}
+
+/// The only item all rucksacks have in common, if there is exactly one.
+fn badge(rucksacks: &[Vec<Item>]) -> Option<&Item> {
+    rucksacks
         .iter()
         .map(|rucksack| rucksack.iter().collect())
-        .reduce(|previous: HashSet<&Item>, next| previous.intersection(&next).map(|i| *i).collect())
-        .unwrap();
-    assert_eq!(
-        badge_set.len(),
-        1,
-        "There is not exactly 1 item matching in all rucksacks"
-    );
-    badge_set.iter().next().unwrap()
+        .reduce(|previous: HashSet<&Item>, next| previous.intersection(&next).copied().collect())?
+        .into_iter()
+        .exactly_one()
+        .ok()
 }
 
 #[cfg(test)]
@@ -168,7 +236,7 @@ mod tests {
         // The first rucksack contains the items vJrwpWtwJgWrhcsFMMfFFhFp, which means its first
         // compartment contains the items vJrwpWtwJgWr, while the second compartment contains the
         // items hcsFMMfFFhFp. The only item type that appears in both compartments is lowercase p.
-        let input = "vJrwpWtwJgWrhcsFMMfFFhFp".chars().collect();
+        let input: Vec<Item> = "vJrwpWtwJgWrhcsFMMfFFhFp".chars().collect();
 
         assert_eq!(find_duplicate_item(&input), &'p');
     }
@@ -177,7 +245,7 @@ mod tests {
     fn test_find_duplicate_item_2() {
         // The second rucksack's compartments contain jqHRNqRjqzjGDLGL and rsFMfFZSrLrFZsSL. The
         // only item type that appears in both compartments is uppercase L.
-        let input = "jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL".chars().collect();
+        let input: Vec<Item> = "jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL".chars().collect();
 
         assert_eq!(find_duplicate_item(&input), &'L');
     }
@@ -186,7 +254,7 @@ mod tests {
     fn test_find_duplicate_item_3() {
         // The third rucksack's compartments contain PmmdzqPrV and vPwwTWBwg; the only common item
         // type is uppercase P.
-        let input = "PmmdzqPrVvPwwTWBwg".chars().collect();
+        let input: Vec<Item> = "PmmdzqPrVvPwwTWBwg".chars().collect();
 
         assert_eq!(find_duplicate_item(&input), &'P');
     }
@@ -194,7 +262,7 @@ mod tests {
     #[test]
     fn test_find_duplicate_item_4() {
         // The fourth rucksack's compartments only share item type v.
-        let input = "wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn".chars().collect();
+        let input: Vec<Item> = "wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn".chars().collect();
 
         assert_eq!(find_duplicate_item(&input), &'v');
     }
@@ -202,14 +270,14 @@ mod tests {
     #[test]
     fn test_find_duplicate_item_5() {
         // The fifth rucksack's compartments only share item type t.
-        let input = "ttgJtRGJQctTZtZT".chars().collect();
+        let input: Vec<Item> = "ttgJtRGJQctTZtZT".chars().collect();
 
         assert_eq!(find_duplicate_item(&input), &'t');
     }
     #[test]
     fn test_find_duplicate_item_6() {
         // The sixth rucksack's compartments only share item type s.
-        let input = "CrZsJsPPZsGzwwsLwLmpwMDw".chars().collect();
+        let input: Vec<Item> = "CrZsJsPPZsGzwwsLwLmpwMDw".chars().collect();
 
         assert_eq!(find_duplicate_item(&input), &'s');
     }
@@ -71,44 +71,341 @@
 /// So, in this example, the number of overlapping assignment pairs is 4.
 ///
 /// In how many assignment pairs do the ranges overlap?
+use crate::DayResult;
 use itertools::Itertools;
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::io::{self, Write};
 
-const INPUT: &str = include_str!("../input/day_04");
+#[cfg(feature = "bundled-input")]
+pub const INPUT: &str = include_str!("../input/day_04");
 
+#[cfg(feature = "bundled-input")]
 pub fn run() {
-    let assignments = load_assignments(INPUT);
+    run_with_input(INPUT, &mut io::stdout()).expect("Couldn't write the answers");
+}
+
+pub fn run_with_input(input: &str, out: &mut dyn Write) -> io::Result<()> {
+    let result = solve(input);
 
-    let fully_contained_pairs = assignments.iter().filter(fully_overlaps).count();
-    println!(
+    writeln!(
+        out,
         "The amount of assignment pairs that fully contain the other is: {}",
-        fully_contained_pairs
-    );
+        result.part_one
+    )?;
 
-    let partially_contained_pairs = assignments.iter().filter(partially_overlaps).count();
-    println!(
+    writeln!(
+        out,
         "The amount of assignment pairs that fully contain the other is: {}",
-        partially_contained_pairs
-    );
+        result.part_two
+    )?;
+
+    writeln!(
+        out,
+        "The amount of sections that are cleaned twice is: {}",
+        total_duplicated_sections(&load_assignments(input))
+    )?;
+    Ok(())
 }
 
-#[derive(Debug, PartialEq)]
-struct Assignment {
+pub fn solve(input: &str) -> DayResult {
+    let assignments = load_assignments(input);
+
+    let (fully_contained_pairs, partially_contained_pairs) = overlap_summary(&assignments);
+
+    DayResult::new(fully_contained_pairs, partially_contained_pairs)
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Assignment {
     begin: u32,
-    end: u32,
+    /// An open end, like in `2-`, goes on to the maximum section.
+    end: Option<u32>,
+    /// Only every `step`-th section from `begin` onwards is part of the assignment, `2-8/2` is
+    /// sections 2, 4, 6 and 8.
+    step: u32,
+}
+
+/// How the end of an assignment should be read: the puzzle uses inclusive ranges (`2-4` is
+/// sections 2, 3 and 4), half-open ranges leave out the end (`2-4` is sections 2 and 3).
+#[derive(Debug, PartialEq, Copy, Clone, Default)]
+pub enum RangeKind {
+    #[default]
+    Inclusive,
+    HalfOpen,
 }
 
 impl Assignment {
-    fn new((begin, end): (u32, u32)) -> Assignment {
-        Assignment { begin, end }
+    pub fn new((begin, end): (u32, u32)) -> Assignment {
+        Assignment::with_step((begin, end), 1)
+    }
+    pub fn with_step((begin, end): (u32, u32), step: u32) -> Assignment {
+        Assignment::with_end(begin, Some(end), step)
+    }
+    /// An assignment from `begin` to the maximum section, like `2-`.
+    pub fn open_ended(begin: u32) -> Assignment {
+        Assignment::with_end(begin, None, 1)
+    }
+    fn with_end(begin: u32, end: Option<u32>, step: u32) -> Assignment {
+        assert!(step > 0, "The step of an assignment can't be 0");
+        Assignment { begin, end, step }
+    }
+    /// The sections in the assignment, in increasing order.
+    pub fn sections(&self) -> impl Iterator<Item = u32> {
+        self.sections_as(RangeKind::Inclusive)
+    }
+    pub fn sections_as(&self, kind: RangeKind) -> impl Iterator<Item = u32> {
+        let (begin, step) = (self.begin, self.step as usize);
+        self.last_as(kind)
+            .into_iter()
+            .flat_map(move |last| (begin..=last).step_by(step))
+    }
+    /// The last section in the assignment, if it has any sections at all.
+    fn last_as(&self, kind: RangeKind) -> Option<u32> {
+        let end = match (self.end, kind) {
+            // an open end has no end to leave out
+            (None, _) => u32::MAX,
+            (Some(end), RangeKind::Inclusive) => end,
+            (Some(end), RangeKind::HalfOpen) => end.checked_sub(1)?,
+        };
+        if end < self.begin {
+            return None;
+        }
+        Some(end - (end - self.begin) % self.step)
     }
-    fn covers(&self, t: u32) -> bool {
-        self.begin <= t && self.end >= t
+    pub fn covers(&self, t: u32) -> bool {
+        self.covers_as(t, RangeKind::Inclusive)
+    }
+    /// Whether section `t` is part of the assignment: it should be in the range and be a whole
+    /// number of steps away from the beginning.
+    pub fn covers_as(&self, t: u32, kind: RangeKind) -> bool {
+        let in_range = self.begin <= t
+            && match (self.end, kind) {
+                (None, _) => true,
+                (Some(end), RangeKind::Inclusive) => end >= t,
+                (Some(end), RangeKind::HalfOpen) => end > t,
+            };
+        in_range && (t - self.begin).is_multiple_of(self.step)
     }
     fn contains(&self, other: &Assignment) -> bool {
-        other.begin >= self.begin && other.end <= self.end
+        self.contains_as(other, RangeKind::Inclusive)
+    }
+    /// Whether all sections of the other assignment are part of this one. This is the case when
+    /// both its first and last section are covered, and its step lands on every step of this one.
+    pub fn contains_as(&self, other: &Assignment, kind: RangeKind) -> bool {
+        match other.last_as(kind) {
+            // an empty range is contained by anything
+            None => true,
+            Some(last) => {
+                self.covers_as(other.begin, kind)
+                    && self.covers_as(last, kind)
+                    && (other.begin == last || other.step.is_multiple_of(self.step))
+            }
+        }
+    }
+    /// The amount of sections in the assignment.
+    pub fn len(&self) -> u32 {
+        self.last_as(RangeKind::Inclusive).map_or(0, |last| {
+            ((last - self.begin) / self.step).saturating_add(1)
+        })
+    }
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    /// The sections both assignments have in common, if there are any. With steps this repeats
+    /// every least common multiple of both steps, from the first section they share.
+    pub fn intersection(&self, other: &Assignment) -> Option<Assignment> {
+        let last = self
+            .last_as(RangeKind::Inclusive)?
+            .min(other.last_as(RangeKind::Inclusive)?);
+        let step = self.step / gcd(self.step, other.step) * other.step;
+        let first = self.first_common_as(other, RangeKind::Inclusive)?;
+        let end = match (self.end, other.end) {
+            (None, None) => None,
+            _ => Some(last),
+        };
+        Some(Assignment::with_end(first, end, step))
+    }
+    /// The sections of both assignments as a single assignment. This is only possible when they
+    /// share their step and overlap or are directly next to each other, otherwise there is a gap.
+    pub fn merge(&self, other: &Assignment) -> Option<Assignment> {
+        // an empty assignment adds nothing to the other one
+        let Some(other_last) = other.last_as(RangeKind::Inclusive) else {
+            return Some(self.clone());
+        };
+        let Some(last) = self.last_as(RangeKind::Inclusive) else {
+            return Some(other.clone());
+        };
+        let (first, second, first_last) = if self.begin <= other.begin {
+            (self, other, last)
+        } else {
+            (other, self, other_last)
+        };
+        let step = first.step;
+        if second.step != step
+            || !(second.begin - first.begin).is_multiple_of(step)
+            || second.begin > first_last.saturating_add(step)
+        {
+            return None;
+        }
+        let end = match (self.end, other.end) {
+            (Some(_), Some(_)) => Some(last.max(other_last)),
+            _ => None,
+        };
+        Some(Assignment::with_end(first.begin, end, step))
+    }
+    /// Whether the assignments have any section in common.
+    fn overlaps_as(&self, other: &Assignment, kind: RangeKind) -> bool {
+        // an empty assignment has no sections to share
+        if self.last_as(kind).is_none() || other.last_as(kind).is_none() {
+            return false;
+        }
+        if self.step == 1 && other.step == 1 {
+            // two ranges overlap when one of them starts inside the other
+            self.covers_as(other.begin, kind) || other.covers_as(self.begin, kind)
+        } else {
+            self.first_common_as(other, kind).is_some()
+        }
+    }
+    /// The first section both assignments have in common. The sections of each assignment are its
+    /// beginning plus a multiple of its step, so this solves both of those congruences at once
+    /// with the Chinese remainder theorem instead of going through the sections one by one.
+    fn first_common_as(&self, other: &Assignment, kind: RangeKind) -> Option<u32> {
+        let last = self.last_as(kind)?.min(other.last_as(kind)?);
+        let first = first_common(self.begin.into(), self.step, other.begin.into(), other.step)?;
+        (first <= u64::from(last)).then_some(first as u32)
+    }
+}
+
+/// Writes the assignment the way it's given in the input, like `2-8`, `2-8/2` with a step or `2-`
+/// with an open end.
+impl fmt::Display for Assignment {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}-", self.begin)?;
+        if let Some(end) = self.end {
+            write!(f, "{}", end)?;
+        }
+        if self.step != 1 {
+            write!(f, "/{}", self.step)?;
+        }
+        Ok(())
+    }
+}
+
+/// Orders assignments by where they begin, then by where they end with an open end last, and then
+/// by their steps.
+impl Ord for Assignment {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let end = |assignment: &Assignment| assignment.end.map_or(u64::MAX, u64::from);
+        self.begin
+            .cmp(&other.begin)
+            .then_with(|| end(self).cmp(&end(other)))
+            .then_with(|| self.step.cmp(&other.step))
+    }
+}
+
+impl PartialOrd for Assignment {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
     }
 }
 
+fn gcd(a: u32, b: u32) -> u32 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+/// The first number from both `a` and `b` on that is `a` plus a multiple of `m` as well as `b` plus
+/// a multiple of `n`. Those numbers repeat every least common multiple of `m` and `n`, so this
+/// solves both congruences at once with the Chinese remainder theorem.
+fn first_common(a: u64, m: u32, b: u64, n: u32) -> Option<u64> {
+    let (a, b) = (i128::from(a), i128::from(b));
+    let g = i128::from(gcd(m, n));
+    let (m, n) = (i128::from(m), i128::from(n));
+    if (b - a) % g != 0 {
+        return None;
+    }
+    // a + m * k is also b plus a multiple of n when m * k = b - a modulo n
+    let n_g = n / g;
+    let k = (b - a) / g % n_g * mod_inverse(m / g, n_g) % n_g;
+    let lcm = m / g * n;
+    let first = a + m * k.rem_euclid(n_g);
+    let start = a.max(b);
+    let first = if first < start {
+        first + (start - first + lcm - 1) / lcm * lcm
+    } else {
+        first
+    };
+    Some(first as u64)
+}
+
+/// The `x` for which `a * x` is 1 modulo `m`, found with the extended Euclidean algorithm. `a` and
+/// `m` should be coprime.
+fn mod_inverse(a: i128, m: i128) -> i128 {
+    let (mut old_r, mut r) = (a.rem_euclid(m), m);
+    let (mut old_s, mut s) = (1, 0);
+    while r != 0 {
+        let quotient = old_r / r;
+        (old_r, r) = (r, old_r - quotient * r);
+        (old_s, s) = (s, old_s - quotient * s);
+    }
+    old_s.rem_euclid(m)
+}
+
+/// Checks that every line is a pair of assignments like `2-4,6-8`, optionally with steps like
+/// `2-8/2` or open ends like `2-`.
+pub fn validate(input: &str) -> Vec<String> {
+    input
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| parse_pair(line).is_none())
+        .map(|(i, line)| format!("line {}: '{}' isn't a pair like 'a-b,c-d'", i + 1, line))
+        .collect()
+}
+
+#[derive(Debug, PartialEq)]
+pub enum AssignmentParseError {
+    /// The input doesn't even have a header row.
+    MissingHeader,
+    /// A line that isn't a pair of assignments, with its line number counting the header.
+    InvalidLine(usize, String),
+    /// The pair at this index, counting from 0, refers to a section past the maximum.
+    OutOfBounds { pair: usize, section: u32 },
+}
+
+/// Reads the pairs from a CSV export, which has a header row like `elf_a,elf_b` before the pairs.
+pub fn load_assignments_csv(
+    input: &str,
+) -> Result<Vec<(Assignment, Assignment)>, AssignmentParseError> {
+    let mut lines = input.lines().enumerate();
+    lines.next().ok_or(AssignmentParseError::MissingHeader)?;
+    lines
+        .map(|(i, line)| {
+            parse_pair(line).ok_or_else(|| AssignmentParseError::InvalidLine(i + 1, line.into()))
+        })
+        .collect()
+}
+
+/// Checks that no assignment ends past section `max`, which usually means a typo like `2-999999`.
+/// Open ends like `2-` go on to wherever the sections end, so for those only the beginning counts.
+pub fn validate_bounds(
+    assignments: &[(Assignment, Assignment)],
+    max: u32,
+) -> Result<(), AssignmentParseError> {
+    for (pair, (a, b)) in assignments.iter().enumerate() {
+        let section = a.end.unwrap_or(a.begin).max(b.end.unwrap_or(b.begin));
+        if section > max {
+            return Err(AssignmentParseError::OutOfBounds { pair, section });
+        }
+    }
+    Ok(())
+}
+
 fn load_assignments(input: &str) -> Vec<(Assignment, Assignment)> {
     input
         .lines()
@@ -122,24 +419,402 @@ fn load_assignments(input: &str) -> Vec<(Assignment, Assignment)> {
         .collect()
 }
 
+fn parse_pair(line: &str) -> Option<(Assignment, Assignment)> {
+    let (a, b) = line.split_once(',')?;
+    Some((parse_assignment(a)?, parse_assignment(b)?))
+}
+
 fn convert_to_assignment(assignment: &str) -> Assignment {
-    Assignment::new(
-        assignment
-            .split('-')
-            .take(2)
-            .map(str::parse)
-            .filter_map(Result::ok)
-            .collect_tuple()
-            .unwrap(),
-    )
+    parse_assignment(assignment)
+        .unwrap_or_else(|| panic!("'{}' isn't an assignment like 'a-b'", assignment))
 }
 
-fn fully_overlaps((a, b): &&(Assignment, Assignment)) -> bool {
-    a.contains(&b) || b.contains(&a)
+fn parse_assignment(assignment: &str) -> Option<Assignment> {
+    let (range, step) = assignment.split_once('/').unwrap_or((assignment, "1"));
+    let step = step.parse().ok().filter(|&step| step > 0)?;
+    let (begin, end) = range.split_once('-')?;
+    let begin = begin.parse().ok()?;
+    let end = match end {
+        "" => None,
+        end => Some(end.parse().ok()?),
+    };
+    Some(Assignment::with_end(begin, end, step))
+}
+
+fn fully_overlaps(pair: &&(Assignment, Assignment)) -> bool {
+    let (a, b) = pair;
+    partially_overlaps(pair) && (a.contains(b) || b.contains(a))
 }
 
 fn partially_overlaps((a, b): &&(Assignment, Assignment)) -> bool {
-    a.covers(b.begin) || a.covers(b.end) || b.covers(a.begin) || b.covers(a.end)
+    a.overlaps_as(b, RangeKind::Inclusive)
+}
+
+/// Counts the pairs that overlap without either assignment containing the other.
+pub fn strictly_partial(assignments: &[(Assignment, Assignment)]) -> usize {
+    assignments
+        .iter()
+        .filter(|pair| partially_overlaps(pair) && !fully_overlaps(pair))
+        .count()
+}
+
+/// Counts the pairs where one assignment fully contains the other, and the pairs that overlap at
+/// all, in a single pass. An assignment that contains the other also overlaps it, so the second
+/// count includes the first one, like the answers to both parts do.
+pub fn overlap_summary(assignments: &[(Assignment, Assignment)]) -> (usize, usize) {
+    assignments
+        .iter()
+        .fold((0, 0), |(full, partial), pair| match classify(pair) {
+            Overlap::Full => (full + 1, partial + 1),
+            Overlap::Partial => (full, partial + 1),
+            Overlap::Disjoint => (full, partial),
+        })
+}
+
+/// How the two assignments of a pair relate to each other.
+#[derive(Debug, PartialEq, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Overlap {
+    /// One assignment fully contains the other.
+    Full,
+    /// The assignments overlap, but neither contains the other.
+    Partial,
+    Disjoint,
+}
+
+pub fn classify(pair: &(Assignment, Assignment)) -> Overlap {
+    if fully_overlaps(&pair) {
+        Overlap::Full
+    } else if partially_overlaps(&pair) {
+        Overlap::Partial
+    } else {
+        Overlap::Disjoint
+    }
+}
+
+/// Summarizes the overlaps of all pairs as JSON. The partial overlap count includes the pairs that
+/// fully overlap, like the answer to part two does.
+#[cfg(feature = "serde")]
+pub fn analysis_json(assignments: &[(Assignment, Assignment)]) -> String {
+    #[derive(serde::Serialize)]
+    struct Analysis<'a> {
+        fully_contained_count: usize,
+        partial_overlap_count: usize,
+        pairs: Vec<PairAnalysis<'a>>,
+    }
+
+    #[derive(serde::Serialize)]
+    struct PairAnalysis<'a> {
+        first: &'a Assignment,
+        second: &'a Assignment,
+        overlap: Overlap,
+    }
+
+    let analysis = Analysis {
+        fully_contained_count: assignments.iter().filter(fully_overlaps).count(),
+        partial_overlap_count: assignments.iter().filter(partially_overlaps).count(),
+        pairs: assignments
+            .iter()
+            .map(|pair| PairAnalysis {
+                first: &pair.0,
+                second: &pair.1,
+                overlap: classify(pair),
+            })
+            .collect(),
+    };
+    serde_json::to_string(&analysis).expect("The analysis should always serialize")
+}
+
+/// Whether one assignment contains the other. Like any full overlap this is also a partial one, so
+/// an empty assignment doesn't count even though it's contained by anything.
+pub fn fully_overlaps_as((a, b): &(Assignment, Assignment), kind: RangeKind) -> bool {
+    a.overlaps_as(b, kind) && (a.contains_as(b, kind) || b.contains_as(a, kind))
+}
+
+pub fn partially_overlaps_as((a, b): &(Assignment, Assignment), kind: RangeKind) -> bool {
+    a.overlaps_as(b, kind)
+}
+
+/// The amount of sections exactly one of the Elves cleans, `|A ∪ B| - |A ∩ B|`.
+pub fn symmetric_difference_size(a: &Assignment, b: &Assignment) -> u32 {
+    let common = a
+        .intersection(b)
+        .map_or(0, |intersection| intersection.len());
+    let union = match a.merge(b) {
+        Some(merged) => merged.len(),
+        None => (a.len() - common).saturating_add(b.len()),
+    };
+    union - common
+}
+
+/// The amount of sections both Elves of a pair clean, summed over all pairs. That's all the
+/// cleaning that could have been skipped.
+pub fn total_duplicated_sections(assignments: &[(Assignment, Assignment)]) -> u32 {
+    assignments
+        .iter()
+        .filter_map(|(a, b)| a.intersection(b))
+        .fold(0, |total, intersection| {
+            total.saturating_add(intersection.len())
+        })
+}
+
+/// The sections cleaned by any of the Elves, as the fewest assignments that don't overlap or touch,
+/// in order. Stepped assignments are only merged with others that have the same step and line up
+/// with them, and dropped when a range without steps contains them. The ones that are left can
+/// still overlap with the others.
+pub fn merged_coverage(assignments: &[(Assignment, Assignment)]) -> Vec<Assignment> {
+    let mut sorted: Vec<&Assignment> = assignments
+        .iter()
+        .flat_map(|(a, b)| [a, b])
+        .filter(|assignment| !assignment.is_empty())
+        .collect();
+    sorted.sort();
+
+    let mut by_step: BTreeMap<u32, Vec<Assignment>> = BTreeMap::new();
+    for assignment in sorted {
+        let merged = by_step.entry(assignment.step).or_default();
+        match merged.last().and_then(|last| last.merge(assignment)) {
+            Some(combined) => *merged.last_mut().expect("There is a last assignment") = combined,
+            None => merged.push(assignment.clone()),
+        }
+    }
+
+    let ranges = by_step.remove(&1).unwrap_or_default();
+    let stepped: Vec<Assignment> = by_step
+        .into_values()
+        .flatten()
+        .filter(|stepped| {
+            !ranges
+                .iter()
+                .any(|range| range.contains_as(stepped, RangeKind::Inclusive))
+        })
+        .collect();
+    let mut coverage = [ranges, stepped].concat();
+    coverage.sort();
+    coverage
+}
+
+/// The index, starting at 0, of the pair whose assignments have the most sections in common,
+/// together with that amount. On a tie it's the first of those pairs. Pairs that don't overlap
+/// at all only count when none of them do.
+pub fn max_overlap_pair(assignments: &[(Assignment, Assignment)]) -> Option<(usize, u32)> {
+    assignments
+        .iter()
+        .map(|(a, b)| {
+            a.intersection(b)
+                .map_or(0, |intersection| intersection.len())
+        })
+        .enumerate()
+        .fold(None, |max, candidate| match max {
+            Some((_, size)) if size >= candidate.1 => max,
+            _ => Some(candidate),
+        })
+}
+
+/// The sections only the first Elf cleans and the sections only the second Elf cleans, in
+/// increasing order. These are listed one by one, so there are none when either assignment has an
+/// open end, as that would list every section up to the maximum.
+pub fn exclusive_sections(a: &Assignment, b: &Assignment) -> Option<(Vec<u32>, Vec<u32>)> {
+    if a.end.is_none() || b.end.is_none() {
+        return None;
+    }
+    let only = |this: &Assignment, other: &Assignment| {
+        this.sections()
+            .filter(|&section| !other.covers(section))
+            .collect()
+    };
+    Some((only(a, b), only(b, a)))
+}
+
+/// The amount of sections strictly between two assignments that neither overlap nor touch, like
+/// section 5 between `2-4` and `6-8`. This looks at the range from the first to the last section
+/// of the assignments, regardless of their steps.
+pub fn gap(a: &Assignment, b: &Assignment) -> Option<u32> {
+    let (a_last, b_last) = (
+        a.last_as(RangeKind::Inclusive)?,
+        b.last_as(RangeKind::Inclusive)?,
+    );
+    let (first_last, second_begin) = if a.begin <= b.begin {
+        (a_last, b.begin)
+    } else {
+        (b_last, a.begin)
+    };
+    second_begin
+        .checked_sub(first_last)?
+        .checked_sub(1)
+        .filter(|&gap| gap > 0)
+}
+
+/// Draws a pair like the puzzle does, a line per assignment with sections 1 up to `width`: a
+/// section in the assignment shows its last digit, other sections show a dot.
+pub fn render_pair(a: &Assignment, b: &Assignment, width: u32) -> String {
+    let render = |assignment: &Assignment| -> String {
+        (1..=width)
+            .map(|t| {
+                if assignment.covers(t) {
+                    char::from_digit(t % 10, 10).expect("A digit should be below 10")
+                } else {
+                    '.'
+                }
+            })
+            .collect()
+    };
+    format!("{}\n{}", render(a), render(b))
+}
+
+/// The section covered by the most assignments, with how many cover it. When several sections are
+/// equally busy the lowest one is returned.
+pub fn busiest_section(assignments: &[(Assignment, Assignment)]) -> Option<(u32, usize)> {
+    let mut busiest = None;
+    sweep(assignments, |first, last, ranges, stepped| {
+        let all = Progression { first, step: 1 };
+        most_covered(all, stepped, last, ranges, &mut busiest);
+    });
+    busiest
+}
+
+/// Looks for the sections where the most of the stepped assignments meet, as the progressions they
+/// have in common, keeping the busiest and lowest section found so far in `busiest`.
+fn most_covered(
+    sections: Progression,
+    stepped: &[(u32, u32)],
+    last: u64,
+    covering: usize,
+    busiest: &mut Option<(u32, usize)>,
+) {
+    let better = busiest.is_none_or(|(section, most)| {
+        covering > most || (covering == most && sections.first < u64::from(section))
+    });
+    if covering > 0 && better {
+        *busiest = Some((sections.first as u32, covering));
+    }
+    for (i, &(begin, step)) in stepped.iter().enumerate() {
+        // the assignments that are left can't make up for the busiest section anymore
+        if busiest.is_some_and(|(_, most)| covering + stepped.len() - i < most) {
+            break;
+        }
+        if let Some(common) = sections.meet(begin, step, last) {
+            most_covered(common, &stepped[i + 1..], last, covering + 1, busiest);
+        }
+    }
+}
+
+/// The amount of sections covered by exactly one assignment, looking at all the pairs together.
+pub fn uniquely_cleaned(assignments: &[(Assignment, Assignment)]) -> usize {
+    let mut unique = 0;
+    sweep(assignments, |first, last, ranges, stepped| {
+        let all = Progression { first, step: 1 };
+        unique += match ranges {
+            // one of the stepped assignments should cover the section, or none when a range does
+            0 => covered_exactly(all, stepped, last, 1),
+            1 => covered_exactly(all, stepped, last, 0),
+            _ => 0,
+        };
+    });
+    unique as usize
+}
+
+/// How many of the sections up to `last` are in exactly `times` of the stepped assignments. This
+/// adds and subtracts the sections every combination of them has in common, which is the
+/// inclusion-exclusion principle, and skips the combinations that have no sections in common.
+fn covered_exactly(sections: Progression, stepped: &[(u32, u32)], last: u64, times: usize) -> u64 {
+    fn included(
+        sections: Progression,
+        stepped: &[(u32, u32)],
+        last: u64,
+        chosen: usize,
+        times: usize,
+    ) -> i128 {
+        let mut total = 0;
+        if chosen >= times {
+            // a section in k assignments is counted once for every way to pick `times` of them
+            let ways = (0..times).fold(1, |ways, i| ways * (chosen - i) as i128 / (i as i128 + 1));
+            let sign = if (chosen - times).is_multiple_of(2) {
+                1
+            } else {
+                -1
+            };
+            let count = (last - sections.first) / sections.step + 1;
+            total += sign * ways * i128::from(count);
+        }
+        for (i, &(begin, step)) in stepped.iter().enumerate() {
+            if let Some(common) = sections.meet(begin, step, last) {
+                total += included(common, &stepped[i + 1..], last, chosen + 1, times);
+            }
+        }
+        total
+    }
+    included(sections, stepped, last, 0, times) as u64
+}
+
+const MAX_SECTION: u64 = u32::MAX as u64;
+
+/// The sections from `first` on, every `step` sections, like the sections several stepped
+/// assignments have in common.
+#[derive(Debug, PartialEq, Copy, Clone)]
+struct Progression {
+    first: u64,
+    step: u64,
+}
+
+impl Progression {
+    /// The sections up to `last` that are also `begin` plus a multiple of `step`, for a `begin`
+    /// that isn't past the first section.
+    fn meet(self, begin: u32, step: u32, last: u64) -> Option<Progression> {
+        if self.step > last - self.first {
+            // only the first section is left before the end
+            let on_step = (self.first - u64::from(begin)).is_multiple_of(u64::from(step));
+            return on_step.then_some(self);
+        }
+        // the step fits in a u32 here, as it's smaller than the distance to the end
+        let first = first_common(self.first, self.step as u32, begin.into(), step)?;
+        let lcm = self.step / u64::from(gcd(self.step as u32, step)) * u64::from(step);
+        (first <= last).then_some(Progression {
+            first,
+            // any step past the maximum section leaves only the first section
+            step: lcm.min(MAX_SECTION + 1),
+        })
+    }
+}
+
+/// Goes through the sections in stretches where the same assignments are active, lowest first.
+/// `visit` gets the first and last section of each stretch, the amount of assignments without a
+/// step covering all of it, and the beginning and step of every stepped assignment in it. This
+/// only looks at where assignments begin and end, never at their sections one by one.
+fn sweep(
+    assignments: &[(Assignment, Assignment)],
+    mut visit: impl FnMut(u64, u64, usize, &[(u32, u32)]),
+) {
+    let ranges: Vec<(u64, u64, u32)> = assignments
+        .iter()
+        .flat_map(|(a, b)| [a, b])
+        .filter_map(|assignment| {
+            let last = assignment.last_as(RangeKind::Inclusive)?;
+            Some((assignment.begin.into(), last.into(), assignment.step))
+        })
+        .collect();
+    let bounds: BTreeSet<u64> = ranges
+        .iter()
+        .flat_map(|&(begin, last, _)| [begin, last + 1])
+        .collect();
+
+    let mut stepped = Vec::new();
+    for (&first, &next) in bounds.iter().tuple_windows() {
+        let active = ranges
+            .iter()
+            .filter(|&&(begin, last, _)| begin <= first && first <= last);
+        stepped.clear();
+        let mut plain = 0;
+        for &(begin, _, step) in active {
+            if step == 1 {
+                plain += 1;
+            } else {
+                stepped.push((begin as u32, step));
+            }
+        }
+        visit(first, next - 1, plain, &stepped);
+    }
 }
 
 #[cfg(test)]
@@ -256,4 +931,561 @@ mod tests {
 
         assert!(partially_overlaps(&&input))
     }
+
+    #[test]
+    fn test_partially_overlaps_as_half_open() {
+        // 2-4,4-6 share section 4 when inclusive, but not when the end is left out
+        let input = (Assignment::new((2, 4)), Assignment::new((4, 6)));
+
+        assert!(partially_overlaps_as(&input, RangeKind::Inclusive));
+        assert!(!partially_overlaps_as(&input, RangeKind::HalfOpen));
+    }
+
+    #[test]
+    fn test_fully_overlaps_as_half_open() {
+        // 2-8,3-7
+        let input = (Assignment::new((2, 8)), Assignment::new((3, 7)));
+
+        assert!(fully_overlaps_as(&input, RangeKind::HalfOpen));
+        assert!(fully_overlaps_as(&input, RangeKind::Inclusive));
+    }
+
+    #[test]
+    fn test_overlaps_as_empty() {
+        // 4-4 leaves out its only section when half-open, and 5-4 has none at all
+        let inside = (Assignment::new((2, 6)), Assignment::new((4, 4)));
+        let same = (Assignment::new((4, 4)), Assignment::new((4, 4)));
+        let reversed = (Assignment::new((5, 4)), Assignment::new((1, 9)));
+
+        for pair in [&inside, &same] {
+            assert!(partially_overlaps_as(pair, RangeKind::Inclusive));
+            assert!(fully_overlaps_as(pair, RangeKind::Inclusive));
+            assert!(!partially_overlaps_as(pair, RangeKind::HalfOpen));
+            assert!(!fully_overlaps_as(pair, RangeKind::HalfOpen));
+        }
+        for kind in [RangeKind::Inclusive, RangeKind::HalfOpen] {
+            assert!(!partially_overlaps_as(&reversed, kind));
+            assert!(!fully_overlaps_as(&reversed, kind));
+        }
+        assert_eq!(classify(&reversed), Overlap::Disjoint);
+    }
+
+    #[test]
+    fn test_overlaps_as_stepped() {
+        // even and odd sections never meet, no matter how far they go
+        let huge = load_assignments("2-1000000000/2,3-1000000001/2\n2-/2,3-/2");
+        assert!(!partially_overlaps_as(&huge[0], RangeKind::Inclusive));
+        assert!(!partially_overlaps_as(&huge[1], RangeKind::Inclusive));
+
+        // 2, 4, 6 and 3, 6 meet at 6, but not when the end is left out
+        let pair = (
+            Assignment::with_step((2, 6), 2),
+            Assignment::with_step((3, 6), 3),
+        );
+        assert!(partially_overlaps_as(&pair, RangeKind::Inclusive));
+        assert!(!partially_overlaps_as(&pair, RangeKind::HalfOpen));
+
+        // 1, 5, 9, ... and 4000000000, 4000000003, ... first meet at 4000000009
+        let far = (
+            Assignment::with_end(1, None, 4),
+            Assignment::with_end(4_000_000_000, None, 3),
+        );
+        assert_eq!(
+            far.0.first_common_as(&far.1, RangeKind::Inclusive),
+            Some(4_000_000_009)
+        );
+        assert_eq!(
+            far.1.first_common_as(&far.0, RangeKind::Inclusive),
+            Some(4_000_000_009)
+        );
+        let near = Assignment::with_step((1, 4_000_000_008), 4);
+        assert_eq!(near.first_common_as(&far.1, RangeKind::Inclusive), None);
+    }
+
+    #[test]
+    fn test_mod_inverse() {
+        assert_eq!(mod_inverse(3, 7), 5);
+        assert_eq!(mod_inverse(-4, 7), 5);
+        assert_eq!(mod_inverse(5, 1), 0);
+    }
+
+    #[test]
+    fn test_covers_as() {
+        let assignment = Assignment::new((2, 4));
+
+        assert!(assignment.covers_as(4, RangeKind::Inclusive));
+        assert!(!assignment.covers_as(4, RangeKind::HalfOpen));
+        assert!(assignment.covers_as(3, RangeKind::HalfOpen));
+    }
+
+    #[test]
+    fn test_classify() {
+        let input = load_assignments("2-4,6-8\n2-3,4-5\n5-7,7-9\n2-8,3-7\n6-6,4-6\n2-6,4-8");
+
+        let expected = vec![
+            Overlap::Disjoint,
+            Overlap::Disjoint,
+            Overlap::Partial,
+            Overlap::Full,
+            Overlap::Full,
+            Overlap::Partial,
+        ];
+
+        assert_eq!(input.iter().map(classify).collect::<Vec<_>>(), expected);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_analysis_json() {
+        let input = load_assignments("2-4,6-8\n2-3,4-5\n5-7,7-9\n2-8,3-7\n6-6,4-6\n2-6,4-8");
+
+        let expected = concat!(
+            r#"{"fully_contained_count":2,"partial_overlap_count":4,"pairs":["#,
+            r#"{"first":{"begin":2,"end":4,"step":1},"second":{"begin":6,"end":8,"step":1},"overlap":"disjoint"},"#,
+            r#"{"first":{"begin":2,"end":3,"step":1},"second":{"begin":4,"end":5,"step":1},"overlap":"disjoint"},"#,
+            r#"{"first":{"begin":5,"end":7,"step":1},"second":{"begin":7,"end":9,"step":1},"overlap":"partial"},"#,
+            r#"{"first":{"begin":2,"end":8,"step":1},"second":{"begin":3,"end":7,"step":1},"overlap":"full"},"#,
+            r#"{"first":{"begin":6,"end":6,"step":1},"second":{"begin":4,"end":6,"step":1},"overlap":"full"},"#,
+            r#"{"first":{"begin":2,"end":6,"step":1},"second":{"begin":4,"end":8,"step":1},"overlap":"partial"}"#,
+            r#"]}"#
+        );
+
+        assert_eq!(analysis_json(&input), expected);
+    }
+
+    #[test]
+    fn test_convert_to_assignment_with_step() {
+        assert_eq!(
+            convert_to_assignment("2-8/2"),
+            Assignment::with_step((2, 8), 2)
+        );
+        assert_eq!(convert_to_assignment("2-8/1"), convert_to_assignment("2-8"));
+    }
+
+    #[test]
+    fn test_covers_with_step() {
+        let assignment = Assignment::with_step((2, 8), 2);
+
+        assert!(assignment.covers(4));
+        assert!(!assignment.covers(5));
+        assert!(!assignment.covers(10));
+        assert_eq!(assignment.sections().collect::<Vec<_>>(), vec![2, 4, 6, 8]);
+    }
+
+    #[test]
+    fn test_contains_with_step() {
+        let assignment = Assignment::with_step((2, 9), 2);
+
+        // 4 and 8
+        assert!(assignment.contains(&Assignment::with_step((4, 9), 4)));
+        // 4, 6 and 8
+        assert!(assignment.contains(&Assignment::with_step((4, 8), 2)));
+        // only 6
+        assert!(assignment.contains(&Assignment::with_step((6, 7), 3)));
+        // 3 isn't part of the assignment
+        assert!(!assignment.contains(&Assignment::new((3, 3))));
+        // neither is 5
+        assert!(!assignment.contains(&Assignment::new((4, 6))));
+    }
+
+    #[test]
+    fn test_partially_overlaps_with_step() {
+        // the even and odd sections never meet
+        let input = (
+            Assignment::with_step((2, 8), 2),
+            Assignment::with_step((3, 9), 2),
+        );
+        assert!(!partially_overlaps(&&input));
+
+        // but 3-9/3 has 6 in common with them
+        let input = (
+            Assignment::with_step((2, 8), 2),
+            Assignment::with_step((3, 9), 3),
+        );
+        assert!(partially_overlaps(&&input));
+    }
+
+    #[test]
+    fn test_len() {
+        assert_eq!(Assignment::new((2, 4)).len(), 3);
+        assert_eq!(Assignment::new((6, 6)).len(), 1);
+        assert_eq!(Assignment::with_step((2, 9), 2).len(), 4);
+        assert!(Assignment::new((5, 4)).is_empty());
+    }
+
+    #[test]
+    fn test_intersection() {
+        assert_eq!(
+            Assignment::new((2, 6)).intersection(&Assignment::new((4, 8))),
+            Some(Assignment::new((4, 6)))
+        );
+        assert_eq!(
+            Assignment::new((2, 4)).intersection(&Assignment::new((6, 8))),
+            None
+        );
+        // 3, 5, 7, 9 and 3, 6, 9 have 3 and 9 in common
+        assert_eq!(
+            Assignment::with_step((3, 9), 2).intersection(&Assignment::with_step((3, 10), 3)),
+            Some(Assignment::with_step((3, 9), 6))
+        );
+    }
+
+    #[test]
+    fn test_merge() {
+        assert_eq!(
+            Assignment::new((4, 8)).merge(&Assignment::new((2, 6))),
+            Some(Assignment::new((2, 8)))
+        );
+        assert_eq!(
+            Assignment::new((2, 4)).merge(&Assignment::new((5, 7))),
+            Some(Assignment::new((2, 7)))
+        );
+        assert_eq!(
+            Assignment::new((2, 4)).merge(&Assignment::new((6, 8))),
+            None
+        );
+        assert_eq!(
+            Assignment::with_step((2, 6), 2).merge(&Assignment::with_step((8, 10), 2)),
+            Some(Assignment::with_step((2, 10), 2))
+        );
+        assert_eq!(
+            Assignment::with_step((2, 6), 2).merge(&Assignment::with_step((3, 7), 2)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_symmetric_difference_size() {
+        // 2, 3 and 7, 8 are only cleaned by one of the Elves
+        assert_eq!(
+            symmetric_difference_size(&Assignment::new((2, 6)), &Assignment::new((4, 8))),
+            4
+        );
+        assert_eq!(
+            symmetric_difference_size(&Assignment::new((2, 4)), &Assignment::new((6, 8))),
+            6
+        );
+        assert_eq!(
+            symmetric_difference_size(&Assignment::new((3, 7)), &Assignment::new((3, 7))),
+            0
+        );
+    }
+
+    #[test]
+    fn test_total_duplicated_sections() {
+        let input = "2-4,6-8\n2-3,4-5\n5-7,7-9\n2-8,3-7\n6-6,4-6\n2-6,4-8";
+
+        // 7, then 3-7, then 6, then 4-6
+        assert_eq!(total_duplicated_sections(&load_assignments(input)), 10);
+        assert_eq!(total_duplicated_sections(&[]), 0);
+    }
+
+    #[test]
+    fn test_merged_coverage() {
+        let input = "2-4,6-8\n2-3,4-5\n5-7,7-9\n2-8,3-7\n6-6,4-6\n2-6,4-8";
+
+        // every section from 2 to 9 is cleaned by someone
+        assert_eq!(
+            merged_coverage(&load_assignments(input)),
+            vec![Assignment::new((2, 9))]
+        );
+        assert_eq!(
+            merged_coverage(&load_assignments("7-9,1-2\n3-4,12-\n2-4/2,11-11")),
+            vec![
+                Assignment::new((1, 4)),
+                Assignment::new((7, 9)),
+                Assignment::open_ended(11),
+            ]
+        );
+        // 6 and 8 aren't in 1-4, and 5 and 7 aren't in the stepped assignments
+        assert_eq!(
+            merged_coverage(&load_assignments("1-4,2-6/2\n4-8/2,10-20/5")),
+            vec![
+                Assignment::new((1, 4)),
+                Assignment::with_step((2, 8), 2),
+                Assignment::with_step((10, 20), 5),
+            ]
+        );
+        assert_eq!(merged_coverage(&[]), vec![]);
+    }
+
+    #[test]
+    fn test_ord() {
+        let mut assignments = vec![
+            Assignment::open_ended(2),
+            Assignment::new((3, 4)),
+            Assignment::with_step((2, 8), 2),
+            Assignment::new((2, 8)),
+        ];
+
+        assignments.sort();
+
+        assert_eq!(
+            assignments,
+            vec![
+                Assignment::new((2, 8)),
+                Assignment::with_step((2, 8), 2),
+                Assignment::open_ended(2),
+                Assignment::new((3, 4)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_strictly_partial() {
+        let input = "2-4,6-8\n2-3,4-5\n5-7,7-9\n2-8,3-7\n6-6,4-6\n2-6,4-8";
+
+        // 5-7,7-9 and 2-6,4-8, the other overlapping pairs contain one another
+        assert_eq!(strictly_partial(&load_assignments(input)), 2);
+        assert_eq!(strictly_partial(&load_assignments("1-5,5-9\n1-2,1-2")), 1);
+    }
+
+    #[test]
+    fn test_exclusive_sections() {
+        assert_eq!(
+            exclusive_sections(&Assignment::new((2, 6)), &Assignment::new((4, 8))),
+            Some((vec![2, 3], vec![7, 8]))
+        );
+        assert_eq!(
+            exclusive_sections(&Assignment::new((3, 7)), &Assignment::new((2, 8))),
+            Some((vec![], vec![2, 8]))
+        );
+        assert_eq!(
+            exclusive_sections(&Assignment::with_step((2, 8), 2), &Assignment::new((3, 6))),
+            Some((vec![2, 8], vec![3, 5]))
+        );
+    }
+
+    #[test]
+    fn test_exclusive_sections_open_ended() {
+        let open = Assignment::open_ended(1);
+
+        assert_eq!(exclusive_sections(&open, &Assignment::new((1, 1))), None);
+        assert_eq!(exclusive_sections(&Assignment::new((1, 1)), &open), None);
+        assert_eq!(exclusive_sections(&open, &open), None);
+    }
+
+    #[test]
+    fn test_load_assignments_csv() {
+        let input = "elf_a,elf_b\n2-4,6-8\n2-3,4-5\n5-7,7-9\n2-8,3-7\n6-6,4-6\n2-6,4-8";
+
+        let expected = load_assignments(input.split_once('\n').unwrap().1);
+        assert_eq!(load_assignments_csv(input), Ok(expected));
+        assert_eq!(load_assignments_csv("elf_a,elf_b"), Ok(vec![]));
+        assert_eq!(
+            load_assignments_csv(""),
+            Err(AssignmentParseError::MissingHeader)
+        );
+        assert_eq!(
+            load_assignments_csv("elf_a,elf_b\n2-4,6-8\n2-4;6-8"),
+            Err(AssignmentParseError::InvalidLine(3, "2-4;6-8".into()))
+        );
+    }
+
+    #[test]
+    fn test_validate_bounds() {
+        let input = "2-4,6-8\n2-3,4-5\n5-7,7-9\n2-8,3-7\n6-6,4-6\n2-6,4-8";
+
+        assert_eq!(validate_bounds(&load_assignments(input), 9), Ok(()));
+        assert_eq!(
+            validate_bounds(&load_assignments(input), 8),
+            Err(AssignmentParseError::OutOfBounds {
+                pair: 2,
+                section: 9
+            })
+        );
+        assert_eq!(
+            validate_bounds(&load_assignments("2-4,6-8\n2-3,4-999999"), 99),
+            Err(AssignmentParseError::OutOfBounds {
+                pair: 1,
+                section: 999999
+            })
+        );
+        assert_eq!(validate_bounds(&load_assignments("2-,6-8"), 8), Ok(()));
+        assert!(validate_bounds(&load_assignments("9-,6-8"), 8).is_err());
+    }
+
+    #[test]
+    fn test_max_overlap_pair() {
+        let input = "2-4,6-8\n2-3,4-5\n5-7,7-9\n2-8,3-7\n6-6,4-6\n2-6,4-8";
+
+        // 2-8,3-7 overlap at 3-7
+        assert_eq!(max_overlap_pair(&load_assignments(input)), Some((3, 5)));
+        assert_eq!(
+            max_overlap_pair(&load_assignments("2-4,6-8\n1-2,2-3\n5-5,5-6")),
+            Some((1, 1))
+        );
+        assert_eq!(max_overlap_pair(&[]), None);
+    }
+
+    #[test]
+    fn test_busiest_section() {
+        let input = "2-4,6-8\n2-3,4-5\n5-7,7-9\n2-8,3-7\n6-6,4-6\n2-6,4-8";
+
+        // section 6 is in all but 2-4, 2-3, 4-5 and 7-9
+        assert_eq!(busiest_section(&load_assignments(input)), Some((6, 8)));
+        assert_eq!(busiest_section(&[]), None);
+    }
+
+    #[test]
+    fn test_uniquely_cleaned() {
+        let input = "2-4,6-8\n2-3,4-5\n5-7,7-9\n2-8,3-7\n6-6,4-6\n2-6,4-8";
+
+        // only 7-9 cleans section 9, every other section is cleaned by several Elves
+        assert_eq!(uniquely_cleaned(&load_assignments(input)), 1);
+        assert_eq!(uniquely_cleaned(&[]), 0);
+        // 1, and 5, 6, 7 and 11 of the stepped assignments, they both have 9
+        assert_eq!(
+            uniquely_cleaned(&load_assignments("1-3,2-2\n5-11/2,3-9/3")),
+            5
+        );
+    }
+
+    #[test]
+    fn test_uniquely_cleaned_huge_steps() {
+        // the odd sections up to 3999999999 and section 2
+        assert_eq!(
+            uniquely_cleaned(&load_assignments("1-4000000000/2,2-2")),
+            2_000_000_000 + 1
+        );
+        // the sections with a remainder of 0, 1 or 2 modulo 6, as 4 is in both assignments and
+        // 3 and 5 are in neither
+        assert_eq!(
+            uniquely_cleaned(&load_assignments("1-3000000000/3,2-3000000000/2")),
+            1_500_000_000
+        );
+    }
+
+    #[test]
+    fn test_uniquely_cleaned_open_ended() {
+        // 1, 3 and 5, and the even sections from 10 to 4294967294
+        assert_eq!(
+            uniquely_cleaned(&load_assignments("1-3,10-/2\n2-2,5-5")),
+            3 + 2147483643
+        );
+        // 1 and 2, and the sections from 5 to 4294967295
+        assert_eq!(
+            uniquely_cleaned(&load_assignments("5-,1-2")),
+            2 + 4294967291
+        );
+    }
+
+    #[test]
+    fn test_busiest_section_stepped() {
+        // 0, 2, 4, 6, 8, 10 and 3, 6, 9 only meet at 6
+        let assignments = load_assignments("0-10/2,3-10/3\n5-7,20-20");
+
+        assert_eq!(busiest_section(&assignments), Some((6, 3)));
+    }
+
+    #[test]
+    fn test_busiest_section_huge_steps() {
+        // the odd sections up to 4000000001 and 1 meet at 1, and only meet 3999999999-4000000000
+        // again at 3999999999
+        let huge = Assignment::with_step((1, 4_000_000_001), 2);
+        assert_eq!(
+            busiest_section(&[(huge.clone(), Assignment::new((1, 1)))]),
+            Some((1, 2))
+        );
+        assert_eq!(
+            busiest_section(&[(
+                huge.clone(),
+                Assignment::new((3_999_999_998, 4_000_000_000))
+            )]),
+            Some((3_999_999_999, 2))
+        );
+        // 1, 4, 7, ... and 2, 4, 6, ... meet every 6 sections from 4, but only 4000000000 of
+        // those is also in 4000000000-4000000001
+        let assignments = [
+            (
+                Assignment::with_step((1, 4_000_000_000), 3),
+                Assignment::with_step((2, 4_000_000_000), 2),
+            ),
+            (huge, Assignment::new((4_000_000_000, 4_000_000_001))),
+        ];
+        assert_eq!(busiest_section(&assignments), Some((4_000_000_000, 3)));
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(Assignment::new((2, 8)).to_string(), "2-8");
+        assert_eq!(Assignment::with_step((2, 8), 2).to_string(), "2-8/2");
+        assert_eq!(convert_to_assignment("6-6/3").to_string(), "6-6/3");
+    }
+
+    #[test]
+    fn test_gap() {
+        let assignments = load_assignments("2-4,6-8\n2-3,4-5\n5-7,7-9\n2-4,10-12");
+
+        let gaps: Vec<Option<u32>> = assignments.iter().map(|(a, b)| gap(a, b)).collect();
+
+        // 2-3 and 4-5 touch, 5-7 and 7-9 overlap
+        assert_eq!(gaps, vec![Some(1), None, None, Some(5)]);
+        assert_eq!(
+            gap(&Assignment::new((6, 8)), &Assignment::new((2, 4))),
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn test_render_pair() {
+        let (a, b) = convert_pair("2-4,6-8");
+        // .234.....  2-4
+        // .....678.  6-8
+        assert_eq!(render_pair(&a, &b, 9), ".234.....\n.....678.");
+
+        let (a, b) = convert_pair("6-6,4-6");
+        // .....6...  6-6
+        // ...456...  4-6
+        assert_eq!(render_pair(&a, &b, 9), ".....6...\n...456...");
+
+        let (a, b) = convert_pair("2-8/3,7-12");
+        assert_eq!(render_pair(&a, &b, 9), ".2..5..8.\n......789");
+    }
+
+    fn convert_pair(line: &str) -> (Assignment, Assignment) {
+        load_assignments(line).remove(0)
+    }
+
+    #[test]
+    fn test_overlap_summary() {
+        let input = "2-4,6-8\n2-3,4-5\n5-7,7-9\n2-8,3-7\n6-6,4-6\n2-6,4-8";
+
+        assert_eq!(overlap_summary(&load_assignments(input)), (2, 4));
+    }
+
+    #[test]
+    fn test_open_ended() {
+        let open = convert_to_assignment("2-");
+        assert_eq!(open, Assignment::open_ended(2));
+        assert_eq!(open.to_string(), "2-");
+        assert!(open.covers(2) && open.covers(u32::MAX) && !open.covers(1));
+
+        let (a, b) = convert_pair("2-,5-7");
+        assert!(a.contains(&b));
+        assert!(!b.contains(&a));
+        assert_eq!(classify(&(a, b)), Overlap::Full);
+    }
+
+    #[test]
+    fn test_open_ended_overlap() {
+        let (a, b) = convert_pair("2-,5-");
+        assert!(a.overlaps_as(&b, RangeKind::Inclusive));
+        assert_eq!(a.intersection(&b), Some(Assignment::open_ended(5)));
+        assert_eq!(a.merge(&b), Some(Assignment::open_ended(2)));
+
+        let (a, b) = convert_pair("5-,2-4");
+        assert_eq!(classify(&(a, b)), Overlap::Disjoint);
+        let (a, b) = convert_pair("5-,2-7");
+        assert_eq!(classify(&(a, b)), Overlap::Partial);
+        assert_eq!(validate("2-,5-7\n3-/2,1-"), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_busiest_section_open_ended() {
+        // 2 and up, every second section from 5 and every third from 3 first meet at 9
+        let assignments = load_assignments("2-,5-/2\n3-/3,1-1");
+
+        assert_eq!(busiest_section(&assignments), Some((9, 3)));
+    }
 }
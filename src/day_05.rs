@@ -127,103 +127,859 @@
 /// Before the rearrangement process finishes, update your simulation so that the Elves know where
 /// they should stand to be ready to unload the final supplies. After the rearrangement procedure
 /// completes, what crate ends up on top of each stack?
+use crate::util::{input_hash, split_on_blank_line};
+use crate::{check_input, AocError, DayOutput, DayResult, RunOptions, Timings};
 use lazy_static::lazy_static;
 use regex::Regex;
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::io::{self, Write};
 use std::num::ParseIntError;
+use std::slice;
 use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering as AtomicOrdering};
+use std::sync::{Mutex, OnceLock};
+use std::time::Instant;
 
-const INPUT: &str = include_str!("../input/day_05");
+#[cfg(feature = "bundled-input")]
+pub const INPUT: &str = include_str!("../input/day_05");
 
+#[cfg(feature = "bundled-input")]
 pub fn run() {
-    let (stacks, instructions) = load_input(INPUT);
+    run_with_input(INPUT, &mut io::stdout()).expect("Couldn't write the answers");
+}
+
+pub fn run_with_input(input: &str, out: &mut dyn Write) -> io::Result<()> {
+    let result = solve(input);
+
+    writeln!(
+        out,
+        "Completing the rearrangement procedure the crates on top of each stack are: {}",
+        result.part_one
+    )?;
+
+    writeln!(
+        out,
+        "Completing the rearrangement procedure with the CraneMover 9001 instructions, the top crates are: {}",
+        result.part_two
+    )?;
+
+    let (_, instructions) = load_input(input);
+    writeln!(out, "{} crate-moves performed.", total_moves(&instructions))?;
+    Ok(())
+}
 
-    let mut stacks_9000 = stacks.clone();
-    for instruction in instructions.iter() {
-        stacks_9000 = instruction.apply_as_crate_mover_9000(stacks_9000);
+pub fn solve(input: &str) -> DayResult {
+    let (stacks, instructions) = load_input(input);
+    if let Err(e) = check_references(&stacks, &instructions) {
+        panic!("The instructions don't match the stacks: {:?}", e);
     }
 
-    let top_crates_9000: String = stacks_9000.iter_mut().filter_map(Vec::pop).collect();
+    let stacks_9000 = rearrange(stacks.clone(), &instructions, Crane::Mover9000);
+    let stacks_9001 = rearrange(stacks, &instructions, Crane::Mover9001);
 
-    println!(
-        "Completing the rearrangement procedure the crates on top of each stack are: {}",
-        top_crates_9000
-    );
+    DayResult::new(top_crates(&stacks_9000), top_crates(&stacks_9001))
+}
 
-    let mut stacks_9001 = stacks.clone();
-    for instruction in instructions.iter() {
-        stacks_9001 = instruction.apply_as_crate_mover_9001(stacks_9001);
+/// The answers of the inputs solved by `solve_cached`, keyed by their `input_hash`.
+static CACHE: OnceLock<Mutex<HashMap<u64, (String, String)>>> = OnceLock::new();
+/// How many times `solve_cached` had to actually solve an input.
+static CACHE_MISSES: AtomicUsize = AtomicUsize::new(0);
+
+/// Like `solve`, but remembers the answers of every input, so solving the same input again only
+/// costs hashing it. The answers stay in memory until `clear_cache` is called, which adds up when
+/// solving many different inputs. The input is validated before it's solved the first time.
+pub fn solve_cached(input: &str) -> Result<(String, String), AocError> {
+    let hash = input_hash(input);
+    let cache = CACHE.get_or_init(|| Mutex::new(HashMap::new()));
+    if let Some(answers) = cache
+        .lock()
+        .expect("The cache lock was poisoned")
+        .get(&hash)
+    {
+        return Ok(answers.clone());
     }
 
-    let top_crates_9001: String = stacks_9001.iter_mut().filter_map(Vec::pop).collect();
+    check_input(5, input)?;
+    CACHE_MISSES.fetch_add(1, AtomicOrdering::Relaxed);
+    let DayResult { part_one, part_two } = solve(input);
+    cache
+        .lock()
+        .expect("The cache lock was poisoned")
+        .insert(hash, (part_one.clone(), part_two.clone()));
+    Ok((part_one, part_two))
+}
 
-    println!(
-        "Completing the rearrangement procedure with the CraneMover 9001 instructions, the top crates are: {}",
-        top_crates_9001
-    );
+/// Forgets all answers remembered by `solve_cached`.
+pub fn clear_cache() {
+    if let Some(cache) = CACHE.get() {
+        cache.lock().expect("The cache lock was poisoned").clear();
+    }
 }
 
-type Stack = Vec<char>;
+/// Solves the puzzle, also giving the timings, traces and checks the options ask for. The input
+/// is validated first, including the stacks the instructions refer to, and isn't part of the
+/// timings.
+pub fn run_with_options(input: &str, options: &RunOptions) -> Result<DayOutput, AocError> {
+    check_input(5, input)?;
+    let mut timings = Timings::default();
 
-#[derive(Debug, PartialEq)]
-struct Instruction {
+    let start = Instant::now();
+    let (stacks, instructions) = load_input(input);
+    let parsed = Instant::now();
+    let stacks_9000 = rearrange(stacks.clone(), &instructions, Crane::Mover9000);
+    let part_one = top_crates(&stacks_9000);
+    let solved_part_one = Instant::now();
+    let stacks_9001 = rearrange(stacks.clone(), &instructions, Crane::Mover9001);
+    let part_two = top_crates(&stacks_9001);
+    let solved_part_two = Instant::now();
+
+    if options.time {
+        timings.parse = Some(parsed - start);
+        timings.part_one = Some(solved_part_one - parsed);
+        timings.part_two = Some(solved_part_two - solved_part_one);
+    }
+
+    if options.check {
+        check_conservation(&stacks, &stacks_9000)?;
+        check_conservation(&stacks, &stacks_9001)?;
+    }
+
+    let mut traces = Vec::new();
+    if options.verbose {
+        traces.push(format!(
+            "{} crate-moves performed.",
+            total_moves(&instructions)
+        ));
+    }
+    if options.trace {
+        for crane in [Crane::Mover9000, Crane::Mover9001] {
+            let trace = top_crates_trace(stacks.clone(), &instructions, crane);
+            traces.extend(
+                trace
+                    .iter()
+                    .enumerate()
+                    .map(|(step, top)| format!("{:?} step {}: {}", crane, step, top)),
+            );
+        }
+    }
+
+    Ok(DayOutput {
+        result: DayResult::new(part_one, part_two),
+        timings,
+        traces,
+    })
+}
+
+/// Solves the puzzle again, checking that both cranes end up with the crates they started with.
+pub fn check(input: &str) -> Result<(), AocError> {
+    let (stacks, instructions) = load_input(input);
+
+    for crane in [Crane::Mover9000, Crane::Mover9001] {
+        let rearranged = rearrange(stacks.clone(), &instructions, crane);
+        check_conservation(&stacks, &rearranged)?;
+    }
+    Ok(())
+}
+
+/// Checks that the rearranged stacks hold exactly the crates of the initial stacks, no crate
+/// should be lost or made up along the way.
+pub fn check_conservation(initial: &[Stack], rearranged: &[Stack]) -> Result<(), AocError> {
+    let mut initial_crates: Vec<char> = initial.iter().flatten().copied().collect();
+    let mut rearranged_crates: Vec<char> = rearranged.iter().flatten().copied().collect();
+    initial_crates.sort_unstable();
+    rearranged_crates.sort_unstable();
+
+    if initial_crates == rearranged_crates {
+        Ok(())
+    } else {
+        Err(AocError::CrateMismatch)
+    }
+}
+
+pub type Stack = Vec<char>;
+
+/// The crane model used to carry out the instructions.
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum Crane {
+    /// Moves one crate at a time.
+    Mover9000,
+    /// Moves all crates of an instruction at once, keeping their order.
+    Mover9001,
+    /// A made-up model that moves one crate at a time, and then turns the whole stack it moved the
+    /// crates to upside down.
+    Mover9002,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Instruction {
     amount: usize,
     from: usize,
     to: usize,
 }
 
 #[derive(Debug, PartialEq)]
-enum ParseInstructionError {
+pub enum ParseInstructionError {
     ParseInt(ParseIntError),
     Regex(String),
+    /// The numbers don't make up an instruction that can be carried out.
+    Invalid(MoveError),
+}
+
+/// Checks that the input has a drawing of the stacks ending in a numbering row, followed by a
+/// blank line and the instructions.
+pub fn validate(input: &str) -> Vec<String> {
+    // the blocks are slices of the input, which tells where they are in it
+    let blocks = split_on_blank_line(input);
+    let offset = |block: &str| block.as_ptr() as usize - input.as_ptr() as usize;
+    let stacks_input = blocks.first().copied().unwrap_or_default();
+    let after_stacks = match blocks.first() {
+        Some(stacks_input) => &input[offset(stacks_input) + stacks_input.len()..],
+        None => "",
+    };
+    // the line break ending the stacks, and then at least a blank line
+    if after_stacks.lines().count() < 2 {
+        return vec!["there is no blank line between the stacks and the instructions".into()];
+    }
+    // a blank instructions section is dropped by the split, which is the same as no instructions
+    let (instructions_input, instructions_start) = match blocks.get(1) {
+        Some(block) => (*block, input[..offset(block)].lines().count() + 1),
+        None => ("", 0),
+    };
+
+    let mut problems = Vec::new();
+    let numbering = stacks_input.lines().last().unwrap_or_default();
+    if !is_numbering_row(numbering) {
+        problems.push(format!(
+            "the stacks don't end in a numbering row: '{}'",
+            numbering
+        ));
+    }
+
+    for (i, line) in instructions_input.lines().enumerate() {
+        if Instruction::from_str(line).is_err() {
+            problems.push(format!(
+                "line {}: '{}' isn't an instruction",
+                instructions_start + i,
+                line
+            ));
+        }
+    }
+
+    if problems.is_empty() {
+        let (stacks, instructions) = load_input(input);
+        if let Err(MoveError::NoStack(stack)) = check_references(&stacks, &instructions) {
+            problems.push(format!(
+                "an instruction refers to stack {}, but there are only {} stacks",
+                stack,
+                stacks.len()
+            ));
+        }
+    }
+    problems
+}
+
+/// Checks that the stacks end in a numbering row counting `1 2 3 ... n` without any gaps, giving
+/// the amount of stacks `n`.
+pub fn verify_numbering(input: &str) -> Result<usize, AocError> {
+    let numbering = split_on_blank_line(input)
+        .first()
+        .and_then(|stacks_input| stacks_input.lines().last())
+        .unwrap_or_default();
+    let problem = if !is_numbering_row(numbering) {
+        format!("the stacks don't end in a numbering row: '{}'", numbering)
+    } else {
+        let numbers: Vec<usize> = numbering
+            .split_whitespace()
+            .map(|number| number.parse().expect("The numbering row should be numbers"))
+            .collect();
+        if numbers.iter().copied().eq(1..=numbers.len()) {
+            return Ok(numbers.len());
+        }
+        format!(
+            "the numbering row doesn't count from 1 to {}: '{}'",
+            numbers.len(),
+            numbering
+        )
+    };
+    Err(AocError::Parse {
+        day: 5,
+        problems: vec![problem],
+    })
 }
 
 fn load_input(input: &str) -> (Vec<Stack>, Vec<Instruction>) {
-    let mut input_iter = input.split("\n\n");
+    let mut input_iter = split_on_blank_line(input).into_iter();
 
     let Some(stacks_input) = input_iter.next() else {
         panic!("There was no stack input")
     };
     let stacks = load_stacks(stacks_input);
 
-    let Some(instructions_input) = input_iter.next() else {
-        panic!("There was no instructions input")
-    };
-    let instructions = instructions_input
-        .lines()
-        .map(Instruction::from_str)
-        .filter_map(Result::ok)
-        .collect();
+    // a blank instructions section is dropped by the split, which is the same as no instructions
+    let instructions_input = input_iter.next().unwrap_or_default();
+    let instructions =
+        load_instructions(instructions_input).expect("The instructions should all be valid");
 
     (stacks, instructions)
 }
 
-fn load_stacks(input: &str) -> Vec<Stack> {
-    let mut stacks = input
+/// Parses the instructions, one per line. Blank lines are skipped, so a section without any
+/// instructions gives an empty list rather than an error.
+pub fn load_instructions(input: &str) -> Result<Vec<Instruction>, ParseInstructionError> {
+    input
         .lines()
-        .flat_map(|line| {
-            line.chars().enumerate().filter_map(|(i, c)| {
-                if c == ' ' || c == '[' || c == ']' {
-                    None
-                } else {
-                    Some((i, c))
-                }
-            })
+        .filter(|line| !line.trim().is_empty())
+        .map(Instruction::from_str)
+        .collect()
+}
+
+pub fn top_crates(stacks: &[Stack]) -> String {
+    stacks.iter().filter_map(|stack| stack.last()).collect()
+}
+
+/// The stack, counting from 1, that holds a crate with the given label. Labels don't have to be
+/// unique, when several crates share the label this is the first stack from the left holding one.
+pub fn final_stack_of(stacks: &[Stack], label: char) -> Option<usize> {
+    stacks
+        .iter()
+        .position(|stack| stack.contains(&label))
+        .map(|index| index + 1)
+}
+
+/// The stack, counting from 1, and label of the crate with the most crates on top of it: the bottom
+/// crate of the tallest stack. When several stacks are equally tall it's the first one from the
+/// left. `None` when there are no crates at all.
+pub fn deepest_crate(stacks: &[Stack]) -> Option<(usize, char)> {
+    stacks
+        .iter()
+        .enumerate()
+        .rev()
+        .max_by_key(|(_, stack)| stack.len())
+        .and_then(|(index, stack)| Some((index + 1, *stack.first()?)))
+}
+
+/// Whether the labels in every stack are in alphabetical order from the bottom up. Equal labels
+/// next to each other are fine, and so are empty stacks.
+pub fn all_stacks_sorted(stacks: &[Stack]) -> bool {
+    stacks
+        .iter()
+        .all(|stack| stack.windows(2).all(|pair| pair[0] <= pair[1]))
+}
+
+/// Why an instruction can't be carried out.
+#[derive(Debug, PartialEq)]
+pub enum MoveError {
+    /// There's no instruction at the index.
+    NoInstruction(usize),
+    /// The instruction doesn't move any crates.
+    NoCrates,
+    /// The instruction moves crates from a stack, counting from 1, onto the same stack.
+    SameStack(usize),
+    /// The instruction refers to a stack, counting from 1, that doesn't exist.
+    NoStack(usize),
+    /// The instruction takes more crates from the stack, counting from 1, than are available.
+    NotEnoughCrates { stack: usize, available: usize },
+}
+
+/// Checks that every instruction moves crates between stacks that exist, before carrying out any
+/// of them. Whether there are enough crates to move is only known while carrying them out.
+pub fn check_references(stacks: &[Stack], instructions: &[Instruction]) -> Result<(), MoveError> {
+    let stack_numbers = 1..=stacks.len();
+    for instruction in instructions {
+        for stack in [instruction.from, instruction.to] {
+            if !stack_numbers.contains(&stack) {
+                return Err(MoveError::NoStack(stack));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Carries out only the instruction at `index`, for stepping through the rearrangement one move at
+/// a time, and returns the crates on top afterwards. The stacks are left alone when the instruction
+/// can't be carried out.
+pub fn step(
+    stacks: &mut Vec<Stack>,
+    instructions: &[Instruction],
+    index: usize,
+    crane: Crane,
+) -> Result<String, MoveError> {
+    let instruction = instructions
+        .get(index)
+        .ok_or(MoveError::NoInstruction(index))?;
+    check_references(stacks, slice::from_ref(instruction))?;
+    if stacks[instruction.from - 1].len() < instruction.amount {
+        return Err(MoveError::NotEnoughCrates {
+            stack: instruction.from,
+            available: stacks[instruction.from - 1].len(),
+        });
+    }
+    *stacks = instruction.apply(std::mem::take(stacks), crane);
+    Ok(top_crates(stacks))
+}
+
+/// How many crates were moved off each stack over the whole rearrangement, for the stacks in
+/// order. The run stops at the first instruction that can't be carried out.
+pub fn throughput(stacks: &[Stack], instructions: &[Instruction]) -> Vec<usize> {
+    let mut moved = vec![0; stacks.len()];
+    let mut stacks = stacks.to_vec();
+    for (index, instruction) in instructions.iter().enumerate() {
+        if step(&mut stacks, instructions, index, Crane::Mover9000).is_err() {
+            break;
+        }
+        moved[instruction.from - 1] += instruction.amount;
+    }
+    moved
+}
+
+/// The index of the first instruction that takes the last crate off a stack, together with that
+/// stack counting from 1. Stacks that are empty from the start don't count. The run stops at the
+/// first instruction that can't be carried out.
+pub fn first_empty_event(
+    stacks: &[Stack],
+    instructions: &[Instruction],
+    crane: Crane,
+) -> Option<(usize, usize)> {
+    let mut stacks = stacks.to_vec();
+    for (index, instruction) in instructions.iter().enumerate() {
+        step(&mut stacks, instructions, index, crane).ok()?;
+        if stacks[instruction.from - 1].is_empty() {
+            return Some((index, instruction.from));
+        }
+    }
+    None
+}
+
+/// How many single crate moves it takes to get the crate `depth_from_top` crates below the top of
+/// a stack, counting from 1, on top of it. Every crate above it has to go to another stack, so it
+/// takes one move per crate above it. `None` when there's no such crate, or when there's no other
+/// stack to move the crates to.
+pub fn moves_to_surface(
+    stacks: &[Stack],
+    stack_index: usize,
+    depth_from_top: usize,
+) -> Option<usize> {
+    let stack = stacks.get(stack_index.checked_sub(1)?)?;
+    let reachable = depth_from_top < stack.len() && (depth_from_top == 0 || stacks.len() > 1);
+    reachable.then_some(depth_from_top)
+}
+
+/// Like `rearrange`, but stops at the first instruction that can't be carried out instead of
+/// panicking, with that instruction counting from 1.
+pub fn try_rearrange(
+    mut stacks: Vec<Stack>,
+    instructions: &[Instruction],
+    crane: Crane,
+) -> Result<Vec<Stack>, AocError> {
+    for index in 0..instructions.len() {
+        step(&mut stacks, instructions, index, crane).map_err(|error| AocError::Rearrangement {
+            instruction: index + 1,
+            error,
+        })?;
+    }
+    Ok(stacks)
+}
+
+/// Checks that every instruction of a valid input can be carried out, so solving it won't run out
+/// of crates. Every crane takes the same amount of crates off the same stacks, so it's enough to
+/// check one of them.
+pub fn check_rearrangement(input: &str) -> Result<(), AocError> {
+    let (stacks, instructions) = load_input(input);
+    try_rearrange(stacks, &instructions, Crane::Mover9000).map(|_| ())
+}
+
+/// Carries out all instructions with the given crane.
+pub fn rearrange(stacks: Vec<Stack>, instructions: &[Instruction], crane: Crane) -> Vec<Stack> {
+    instructions.iter().fold(stacks, |stacks, instruction| {
+        instruction.apply(stacks, crane)
+    })
+}
+
+/// Carries out the instructions one at a time, yielding the stacks after every instruction.
+pub fn steps(
+    stacks: Vec<Stack>,
+    instructions: &[Instruction],
+    crane: Crane,
+) -> impl Iterator<Item = Vec<Stack>> + '_ {
+    instructions
+        .iter()
+        .scan(stacks, move |stacks, instruction| {
+            *stacks = instruction.apply(std::mem::take(stacks), crane);
+            Some(stacks.clone())
         })
-        .fold(Vec::new(), |mut vec, (position, c)| {
-            let index = position / 4;
+}
+
+/// The crates on top of the stacks at the start and after every instruction. The trace ends at
+/// the first instruction that can't be carried out.
+pub fn top_crates_trace(
+    mut stacks: Vec<Stack>,
+    instructions: &[Instruction],
+    crane: Crane,
+) -> Vec<String> {
+    let mut trace = vec![top_crates(&stacks)];
+    trace.extend(
+        (0..instructions.len())
+            .map_while(|index| step(&mut stacks, instructions, index, crane).ok()),
+    );
+    trace
+}
+
+/// The stacks in a single line, bottom to top and separated by `|`, like `ZN|MCD|P`.
+pub fn encode_stacks(stacks: &[Stack]) -> String {
+    stacks
+        .iter()
+        .map(|stack| stack.iter().collect::<String>())
+        .collect::<Vec<_>>()
+        .join("|")
+}
+
+/// A log of the rearrangement for other tools to replay, with a line for the stacks at the start
+/// and a line per instruction with the stacks after it, like `move 1 from 2 to 1: ZND|MC|P`.
+pub fn simulation_log(input: &str, crane: Crane) -> Result<String, AocError> {
+    check_input(5, input)?;
+    let (mut stacks, instructions) = load_input(input);
+
+    let mut log = vec![format!("start: {}", encode_stacks(&stacks))];
+    for (index, instruction) in instructions.iter().enumerate() {
+        step(&mut stacks, &instructions, index, crane).map_err(|error| {
+            AocError::Rearrangement {
+                instruction: index + 1,
+                error,
+            }
+        })?;
+        log.push(format!("{}: {}", instruction, encode_stacks(&stacks)));
+    }
+    Ok(log.join("\n"))
+}
+
+/// Lists the crates that are never on top of a stack, neither at the start nor after any of the
+/// instructions carried out by the CrateMover 9000. Each label is listed once, in the order it is
+/// first found going through the stacks bottom to top. The run stops at the first instruction that
+/// can't be carried out.
+pub fn always_buried(stacks: &[Stack], instructions: &[Instruction]) -> Vec<char> {
+    let mut on_top: HashSet<char> = top_crates(stacks).chars().collect();
+    let mut rearranged = stacks.to_vec();
+    for index in 0..instructions.len() {
+        match step(&mut rearranged, instructions, index, Crane::Mover9000) {
+            Ok(top) => on_top.extend(top.chars()),
+            Err(_) => break,
+        }
+    }
+
+    let mut buried = Vec::new();
+    for label in stacks.iter().flatten() {
+        if !on_top.contains(label) && !buried.contains(label) {
+            buried.push(*label);
+        }
+    }
+    buried
+}
+
+/// The total amount of crates moved by all instructions together.
+pub fn total_moves(instructions: &[Instruction]) -> usize {
+    instructions
+        .iter()
+        .map(|instruction| instruction.amount)
+        .sum()
+}
+
+/// The stack, counting from 1, each instruction takes crates from, in order.
+pub fn source_sequence(instructions: &[Instruction]) -> Vec<usize> {
+    instructions
+        .iter()
+        .map(|instruction| instruction.from)
+        .collect()
+}
+
+/// Some numbers describing a list of instructions.
+#[derive(Debug, PartialEq)]
+pub struct InstructionStats {
+    /// The amount of crates moved over all instructions.
+    pub total_moves: usize,
+    /// The most crates a single instruction moves.
+    pub max_amount: usize,
+    /// The amount of different stacks crates are taken from.
+    pub sources: usize,
+    /// The amount of different stacks crates are put on.
+    pub destinations: usize,
+    /// The `(from, to)` stacks that are used by the most instructions, the first one in the
+    /// instructions on a tie.
+    pub most_used_route: Option<(usize, usize)>,
+}
+
+pub fn instruction_stats(instructions: &[Instruction]) -> InstructionStats {
+    let mut routes: HashMap<(usize, usize), usize> = HashMap::new();
+    for instruction in instructions {
+        *routes
+            .entry((instruction.from, instruction.to))
+            .or_insert(0) += 1;
+    }
+    let mut most_used_route = None;
+    for instruction in instructions {
+        let route = (instruction.from, instruction.to);
+        if most_used_route.is_none_or(|most_used| routes[&route] > routes[&most_used]) {
+            most_used_route = Some(route);
+        }
+    }
+    InstructionStats {
+        total_moves: total_moves(instructions),
+        max_amount: instructions
+            .iter()
+            .map(|instruction| instruction.amount)
+            .max()
+            .unwrap_or(0),
+        sources: instructions
+            .iter()
+            .map(|instruction| instruction.from)
+            .collect::<HashSet<_>>()
+            .len(),
+        destinations: instructions
+            .iter()
+            .map(|instruction| instruction.to)
+            .collect::<HashSet<_>>()
+            .len(),
+        most_used_route,
+    }
+}
+
+/// Groups the instructions, by index, into batches that could be carried out at the same time: no
+/// two instructions in a batch touch the same stack. Carrying out the batches in order gives the
+/// same result as the instructions one by one, as every instruction comes in a later batch than
+/// the instructions before it that touch one of its stacks.
+pub fn independent_batches(instructions: &[Instruction]) -> Vec<Vec<usize>> {
+    // the first batch every stack is free again
+    let mut free_from: HashMap<usize, usize> = HashMap::new();
+    let mut batches: Vec<Vec<usize>> = Vec::new();
+    for (i, instruction) in instructions.iter().enumerate() {
+        let batch = [instruction.from, instruction.to]
+            .iter()
+            .map(|stack| free_from.get(stack).copied().unwrap_or(0))
+            .max()
+            .unwrap_or(0);
+        if batches.len() <= batch {
+            batches.resize(batch + 1, Vec::new());
+        }
+        batches[batch].push(i);
+        free_from.insert(instruction.from, batch + 1);
+        free_from.insert(instruction.to, batch + 1);
+    }
+    batches
+}
+
+/// Merges adjacent instructions that move crates along the same route into a single instruction.
+///
+/// This only holds for the CrateMover 9000: moving `a` and then `b` crates one at a time puts them
+/// in the same order as moving `a + b` crates one at a time. The CrateMover 9001 keeps the order
+/// of every batch it moves, so merging its instructions would change the result.
+pub fn coalesce_9000(instructions: &[Instruction]) -> Vec<Instruction> {
+    let mut coalesced: Vec<Instruction> = Vec::new();
+    for instruction in instructions {
+        match coalesced.last_mut() {
+            Some(last) if last.from == instruction.from && last.to == instruction.to => {
+                last.amount += instruction.amount;
+            }
+            _ => coalesced.push(instruction.clone()),
+        }
+    }
+    coalesced
+}
+
+/// The most instructions in a row that put crates on the same stack.
+pub fn longest_same_destination_run(instructions: &[Instruction]) -> usize {
+    instructions
+        .chunk_by(|a, b| a.to == b.to)
+        .map(<[Instruction]>::len)
+        .max()
+        .unwrap_or(0)
+}
+
+/// Finds instructions for the CrateMover 9000 that rearrange the `from` stacks into the `to`
+/// stacks, if that's possible at all. The instructions aren't necessarily the shortest ones.
+///
+/// With three or more stacks any order can be reached, so this only needs the same crates. The
+/// stacks are then built one at a time from the bottom up: for every next crate the closest
+/// matching crate that isn't in place yet is dug up, putting the crates on top of it aside.
+///
+/// With two stacks moving crates back and forth is like moving a cut through the first stack
+/// followed by the second one upside down, so only the states with the same sequence are reachable.
+pub fn instructions_between(from: &[Stack], to: &[Stack]) -> Option<Vec<Instruction>> {
+    let mut from_crates: Vec<char> = from.iter().flatten().copied().collect();
+    let mut to_crates: Vec<char> = to.iter().flatten().copied().collect();
+    from_crates.sort_unstable();
+    to_crates.sort_unstable();
+    if from.len() != to.len() || from_crates != to_crates {
+        return None;
+    }
+
+    match from.len() {
+        0 | 1 => (from == to).then(Vec::new),
+        2 => {
+            let sequence = |stacks: &[Stack]| -> Vec<char> {
+                stacks[0]
+                    .iter()
+                    .chain(stacks[1].iter().rev())
+                    .copied()
+                    .collect()
+            };
+            if sequence(from) != sequence(to) {
+                return None;
+            }
+            let (have, want) = (from[0].len(), to[0].len());
+            let instruction = match have.cmp(&want) {
+                Ordering::Equal => return Some(Vec::new()),
+                Ordering::Greater => Instruction::new(have - want, 1, 2),
+                Ordering::Less => Instruction::new(want - have, 2, 1),
+            };
+            Some(vec![
+                instruction.expect("The crates move between different stacks")
+            ])
+        }
+        _ => Some(build_stacks(from, to)),
+    }
+}
+
+fn build_stacks(from: &[Stack], to: &[Stack]) -> Vec<Instruction> {
+    let stack_count = from.len();
+    let mut stacks = from.to_vec();
+    let mut instructions = Vec::new();
+    // the bottom crates of each stack that are already in their final place
+    let mut in_place = vec![0; stack_count];
+
+    let mut move_crates = |stacks: &mut Vec<Stack>, amount: usize, from: usize, to: usize| {
+        if amount > 0 {
+            let instruction = Instruction::new(amount, from + 1, to + 1)
+                .expect("The crates move between different stacks");
+            *stacks = instruction.apply(std::mem::take(stacks), Crane::Mover9000);
+            instructions.push(instruction);
+        }
+    };
+    let other_than = |a: usize, b: usize| {
+        (0..stack_count)
+            .find(|&t| t != a && t != b)
+            .expect("There should be at least three stacks")
+    };
+
+    for i in 0..stack_count {
+        for (k, &wanted) in to[i].iter().enumerate() {
+            in_place[i] = k;
+            // clear away whatever lies on top of the crates in place
+            let loose = stacks[i].len() - k;
+            move_crates(&mut stacks, loose, i, other_than(i, i));
+
+            let (j, above) = (0..stack_count)
+                .filter(|&j| j != i)
+                .filter_map(|j| {
+                    stacks[j][in_place[j]..]
+                        .iter()
+                        .rev()
+                        .position(|&label| label == wanted)
+                        .map(|above| (j, above))
+                })
+                .min_by_key(|&(_, above)| above)
+                .expect("The stacks should hold the same crates");
+            move_crates(&mut stacks, above, j, other_than(i, j));
+            move_crates(&mut stacks, 1, j, i);
+        }
+        let loose = stacks[i].len() - to[i].len();
+        move_crates(&mut stacks, loose, i, other_than(i, i));
+        in_place[i] = to[i].len();
+    }
+
+    coalesce_9000(&instructions)
+}
+
+/// Builds the stacks from lists of crates, one list per stack going bottom to top, for when the
+/// starting state isn't a drawing. There should be at least one stack, and every label should be
+/// one a drawing could hold: no whitespace or brackets.
+pub fn stacks_from_columns(columns: Vec<Vec<char>>) -> Result<Vec<Stack>, AocError> {
+    let mut problems = Vec::new();
+    if columns.is_empty() {
+        problems.push("there are no stacks".to_string());
+    }
+    for (index, column) in columns.iter().enumerate() {
+        let invalid = column
+            .iter()
+            .find(|label| label.is_whitespace() || matches!(label, '[' | ']'));
+        if let Some(label) = invalid {
+            problems.push(format!(
+                "stack {} has {:?}, which isn't a crate label",
+                index + 1,
+                label
+            ));
+        }
+    }
+    if problems.is_empty() {
+        Ok(columns)
+    } else {
+        Err(AocError::Parse { day: 5, problems })
+    }
+}
+
+fn load_stacks(input: &str) -> Vec<Stack> {
+    let mut lines: Vec<&str> = input.lines().collect();
+    // the numbering row decides how many stacks there are, even when some of them are empty, and
+    // how many columns every stack takes
+    let numbers: Vec<usize> = match lines.last() {
+        Some(&numbering) if is_numbering_row(numbering) => {
+            lines.pop();
+            numbering
+                .char_indices()
+                .filter(|&(i, c)| {
+                    !c.is_whitespace() && (i == 0 || numbering[..i].ends_with(char::is_whitespace))
+                })
+                .map(|(i, _)| i)
+                .collect()
+        }
+        _ => Vec::new(),
+    };
+    let first_column = numbers.first().copied().unwrap_or(1);
+    let stride = match numbers[..] {
+        [first, second, ..] => second - first,
+        _ => 4,
+    };
+
+    // go through the crates bottom to top
+    lines.iter().rev().flat_map(|line| crate_labels(line)).fold(
+        vec![Vec::new(); numbers.len()],
+        |mut vec, (position, label)| {
+            // the label belongs to the stack whose number it's closest to
+            let index = (position + stride / 2).saturating_sub(first_column) / stride;
             if vec.len() <= index {
                 vec.resize(index + 1, Vec::new());
             }
             let inner_vec = vec.get_mut(index).expect("The vec wasn't resized properly");
-            inner_vec.insert(0, c);
+            inner_vec.push(label);
             vec
-        });
+        },
+    )
+}
+
+/// Whether the line is the row numbering the stacks, rather than a row of crates. Crates are
+/// always in brackets, so even crates labelled with digits can't be mistaken for it.
+fn is_numbering_row(line: &str) -> bool {
+    !line.trim().is_empty()
+        && line
+            .split_whitespace()
+            .all(|index| index.parse::<usize>().is_ok())
+}
 
-    for stack in stacks.iter_mut() {
-        // remove the index indicators
-        stack.remove(0);
+/// The crates in a row of the drawing, as the column of their label and the label itself. Any
+/// character other than a space or a bracket can be a label.
+fn crate_labels(line: &str) -> impl Iterator<Item = (usize, char)> {
+    let columns: Vec<char> = line.chars().collect();
+    (0..columns.len()).filter_map(move |i| match columns.get(i..i + 3) {
+        Some(&['[', label, ']']) if !matches!(label, ' ' | '[' | ']') => Some((i + 1, label)),
+        _ => None,
+    })
+}
+
+/// Writes the instruction the way it's given in the input, like `move 1 from 2 to 1`.
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "move {} from {} to {}", self.amount, self.from, self.to)
     }
-    stacks
 }
 
 impl FromStr for Instruction {
@@ -231,18 +987,16 @@ impl FromStr for Instruction {
 
     fn from_str(line: &str) -> Result<Self, Self::Err> {
         lazy_static! {
+            // hand-written instructions can say how many crates, like 'move 3 crates from 1 to 3'
             static ref LINE_EXPRESSION: Regex =
-                Regex::new(r"^move (\d+) from (\d+) to (\d+)$").unwrap();
+                Regex::new(r"^move (\d+)(?: crates?)? from (\d+) to (\d+)$").unwrap();
         }
         let captures = LINE_EXPRESSION
             .captures(line)
-            .and_then(|cap| Some((cap.get(1), cap.get(2), cap.get(3))));
+            .map(|cap| (cap.get(1), cap.get(2), cap.get(3)));
         match captures {
             Some((Some(amount), Some(from), Some(to))) => {
-                match Instruction::parse_str(amount.as_str(), from.as_str(), to.as_str()) {
-                    Err(e) => Err(ParseInstructionError::ParseInt(e)),
-                    Ok(i) => Ok(i),
-                }
+                Instruction::parse_str(amount.as_str(), from.as_str(), to.as_str())
             }
             _ => Err(ParseInstructionError::Regex("Couldn't match regex".into())),
         }
@@ -250,12 +1004,33 @@ impl FromStr for Instruction {
 }
 
 impl Instruction {
-    fn parse_str(amount: &str, from: &str, to: &str) -> Result<Instruction, ParseIntError> {
-        Ok(Instruction {
-            amount: amount.parse()?,
-            from: from.parse()?,
-            to: to.parse()?,
-        })
+    fn apply(&self, stacks: Vec<Stack>, crane: Crane) -> Vec<Stack> {
+        match crane {
+            Crane::Mover9000 => self.apply_as_crate_mover_9000(stacks),
+            Crane::Mover9001 => self.apply_as_crate_mover_9001(stacks),
+            Crane::Mover9002 => self.apply_as_crate_mover_9002(stacks),
+        }
+    }
+
+    /// An instruction to move `amount` crates from stack `from` to stack `to`, counting from 1. It
+    /// should move at least one crate, between two different stacks.
+    pub fn new(amount: usize, from: usize, to: usize) -> Result<Instruction, MoveError> {
+        if amount == 0 {
+            return Err(MoveError::NoCrates);
+        }
+        if let Some(stack) = [from, to].into_iter().find(|&stack| stack == 0) {
+            return Err(MoveError::NoStack(stack));
+        }
+        if from == to {
+            return Err(MoveError::SameStack(from));
+        }
+        Ok(Instruction { amount, from, to })
+    }
+
+    fn parse_str(amount: &str, from: &str, to: &str) -> Result<Instruction, ParseInstructionError> {
+        let parse = |number: &str| number.parse().map_err(ParseInstructionError::ParseInt);
+        Instruction::new(parse(amount)?, parse(from)?, parse(to)?)
+            .map_err(ParseInstructionError::Invalid)
     }
 
     fn apply_as_crate_mover_9000(&self, mut stacks: Vec<Stack>) -> Vec<Stack> {
@@ -269,6 +1044,12 @@ impl Instruction {
         stacks
     }
 
+    fn apply_as_crate_mover_9002(&self, stacks: Vec<Stack>) -> Vec<Stack> {
+        let mut stacks = self.apply_as_crate_mover_9000(stacks);
+        stacks[self.to - 1].reverse();
+        stacks
+    }
+
     fn apply_as_crate_mover_9001(&self, mut stacks: Vec<Stack>) -> Vec<Stack> {
         let mut buffer = Vec::new();
         for _ in 0..self.amount {
@@ -291,6 +1072,8 @@ impl Instruction {
 mod tests {
     use super::*;
 
+    const EXAMPLE: &str = "    [D]    \n[N] [C]    \n[Z] [M] [P]\n 1   2   3 \n\nmove 1 from 2 to 1\nmove 3 from 1 to 3\nmove 2 from 2 to 1\nmove 1 from 1 to 2";
+
     #[test]
     fn test_load_stacks() {
         let input = "     [D]\n [N] [C]\n [Z] [M] [P]\n  1   2   3";
@@ -300,19 +1083,83 @@ mod tests {
         assert_eq!(load_stacks(input), expected);
     }
 
+    #[test]
+    fn test_load_stacks_other_labels() {
+        let input = "[1] [2]\n[3] [4] [#]\n 1   2   3";
+
+        let expected = vec![vec!['3', '1'], vec!['4', '2'], vec!['#']];
+        assert_eq!(load_stacks(input), expected);
+    }
+
+    #[test]
+    fn test_load_stacks_without_numbering() {
+        // the bottom row is crates, not a numbering row
+        let input = "[1]\n[2] [3]";
+
+        let expected = vec![vec!['2', '1'], vec!['3']];
+        assert_eq!(load_stacks(input), expected);
+    }
+
+    #[test]
+    fn test_load_stacks_wider_columns() {
+        let input = "     [D]\n[N]  [C]\n[Z]  [M]  [P]\n 1    2    3";
+
+        let expected = vec![vec!['Z', 'N'], vec!['M', 'C', 'D'], vec!['P']];
+        assert_eq!(load_stacks(input), expected);
+    }
+
+    #[test]
+    fn test_load_stacks_empty_columns() {
+        // the first and last stack don't hold any crates
+        let input = "    [D]\n    [C] [N]\n    [M] [Z]\n 1   2   3   4";
+
+        let expected = vec![vec![], vec!['M', 'C', 'D'], vec!['Z', 'N'], vec![]];
+
+        assert_eq!(load_stacks(input), expected);
+    }
+
     #[test]
     fn test_instruction_from_str() {
         let input = "move 1 from 2 to 1";
 
-        let expected = Instruction {
-            amount: 1,
-            from: 2,
-            to: 1,
-        };
+        let expected = Instruction::new(1, 2, 1).unwrap();
 
         assert_eq!(Instruction::from_str(input), Ok(expected))
     }
 
+    #[test]
+    fn test_instruction_from_str_with_crates() {
+        assert_eq!(
+            Instruction::from_str("move 1 crate from 2 to 1"),
+            Ok(Instruction::new(1, 2, 1).unwrap())
+        );
+        assert_eq!(
+            Instruction::from_str("move 3 crates from 1 to 3"),
+            Ok(Instruction::new(3, 1, 3).unwrap())
+        );
+        assert!(Instruction::from_str("move 3 boxes from 1 to 3").is_err());
+    }
+
+    #[test]
+    fn test_instruction_new() {
+        assert_eq!(
+            Instruction::new(3, 1, 3),
+            Ok(Instruction {
+                amount: 3,
+                from: 1,
+                to: 3
+            })
+        );
+        assert_eq!(Instruction::new(0, 1, 3), Err(MoveError::NoCrates));
+        assert_eq!(Instruction::new(3, 0, 3), Err(MoveError::NoStack(0)));
+        assert_eq!(Instruction::new(3, 1, 0), Err(MoveError::NoStack(0)));
+        assert_eq!(Instruction::new(3, 2, 2), Err(MoveError::SameStack(2)));
+        assert_eq!(
+            Instruction::from_str("move 1 from 2 to 2"),
+            Err(ParseInstructionError::Invalid(MoveError::SameStack(2)))
+        );
+    }
+
     #[test]
     fn test_apply_as_crane_mover_9000_instruction_1() {
         //     [D]
@@ -322,11 +1169,7 @@ mod tests {
         let input = vec![vec!['Z', 'N'], vec!['M', 'C', 'D'], vec!['P']];
 
         // move 1 from 2 to 1
-        let instruction = Instruction {
-            amount: 1,
-            from: 2,
-            to: 1,
-        };
+        let instruction = Instruction::new(1, 2, 1).unwrap();
 
         // [D]
         // [N] [C]
@@ -346,11 +1189,7 @@ mod tests {
         let input = vec![vec!['Z', 'N', 'D'], vec!['M', 'C'], vec!['P']];
 
         // move 3 from 1 to 3
-        let instruction = Instruction {
-            amount: 3,
-            from: 1,
-            to: 3,
-        };
+        let instruction = Instruction::new(3, 1, 3).unwrap();
 
         //         [Z]
         //         [N]
@@ -372,11 +1211,7 @@ mod tests {
         let input = vec![vec![], vec!['M', 'C'], vec!['P', 'D', 'N', 'Z']];
 
         // move 2 from 2 to 1
-        let instruction = Instruction {
-            amount: 2,
-            from: 2,
-            to: 1,
-        };
+        let instruction = Instruction::new(2, 2, 1).unwrap();
 
         //         [Z]
         //         [N]
@@ -398,11 +1233,7 @@ mod tests {
         let input = vec![vec!['C', 'M'], vec![], vec!['P', 'D', 'N', 'Z']];
 
         // move 1 from 1 to 2
-        let instruction = Instruction {
-            amount: 1,
-            from: 1,
-            to: 2,
-        };
+        let instruction = Instruction::new(1, 1, 2).unwrap();
 
         //         [Z]
         //         [N]
@@ -423,11 +1254,7 @@ mod tests {
         let input = vec![vec!['Z', 'N'], vec!['M', 'C', 'D'], vec!['P']];
 
         // move 1 from 2 to 1
-        let instruction = Instruction {
-            amount: 1,
-            from: 2,
-            to: 1,
-        };
+        let instruction = Instruction::new(1, 2, 1).unwrap();
 
         // [D]
         // [N] [C]
@@ -447,11 +1274,7 @@ mod tests {
         let input = vec![vec!['Z', 'N', 'D'], vec!['M', 'C'], vec!['P']];
 
         // move 3 from 1 to 3
-        let instruction = Instruction {
-            amount: 3,
-            from: 1,
-            to: 3,
-        };
+        let instruction = Instruction::new(3, 1, 3).unwrap();
 
         //         [D]
         //         [N]
@@ -473,11 +1296,7 @@ mod tests {
         let input = vec![vec![], vec!['M', 'C'], vec!['P', 'Z', 'N', 'D']];
 
         // move 2 from 2 to 1
-        let instruction = Instruction {
-            amount: 2,
-            from: 2,
-            to: 1,
-        };
+        let instruction = Instruction::new(2, 2, 1).unwrap();
 
         //         [D]
         //         [N]
@@ -499,11 +1318,7 @@ mod tests {
         let input = vec![vec!['M', 'C'], vec![], vec!['P', 'Z', 'N', 'D']];
 
         // move 1 from 1 to 2
-        let instruction = Instruction {
-            amount: 1,
-            from: 1,
-            to: 2,
-        };
+        let instruction = Instruction::new(1, 1, 2).unwrap();
 
         //         [D]
         //         [N]
@@ -514,4 +1329,594 @@ mod tests {
 
         assert_eq!(instruction.apply_as_crate_mover_9001(input), expected);
     }
+
+    #[test]
+    fn test_coalesce_9000_same_route() {
+        // move 1 from 2 to 1
+        // move 2 from 2 to 1
+        let input = vec![
+            Instruction::new(1, 2, 1).unwrap(),
+            Instruction::new(2, 2, 1).unwrap(),
+        ];
+
+        let expected = vec![Instruction::new(3, 2, 1).unwrap()];
+
+        assert_eq!(coalesce_9000(&input), expected);
+    }
+
+    #[test]
+    fn test_coalesce_9000_different_routes() {
+        // move 1 from 2 to 1
+        // move 3 from 1 to 3
+        // move 2 from 2 to 1
+        let input = vec![
+            Instruction::new(1, 2, 1).unwrap(),
+            Instruction::new(3, 1, 3).unwrap(),
+            Instruction::new(2, 2, 1).unwrap(),
+        ];
+
+        assert_eq!(coalesce_9000(&input), input);
+    }
+
+    #[test]
+    fn test_longest_same_destination_run() {
+        let instructions = load_instructions(
+            "move 1 from 1 to 2\nmove 1 from 3 to 2\nmove 2 from 2 to 3\n\
+             move 1 from 1 to 3\nmove 1 from 2 to 3\nmove 1 from 3 to 1",
+        )
+        .unwrap();
+
+        // the third to fifth instruction all go to stack 3
+        assert_eq!(longest_same_destination_run(&instructions), 3);
+        assert_eq!(longest_same_destination_run(&instructions[..2]), 2);
+        assert_eq!(longest_same_destination_run(&[]), 0);
+    }
+
+    #[test]
+    fn test_total_moves() {
+        let (_, instructions) = load_input(EXAMPLE);
+
+        assert_eq!(total_moves(&instructions), 7);
+    }
+
+    #[test]
+    fn test_source_sequence() {
+        let (_, instructions) = load_input(EXAMPLE);
+
+        assert_eq!(source_sequence(&instructions), vec![2, 1, 2, 1]);
+    }
+
+    #[test]
+    fn test_instruction_stats() {
+        let (_, instructions) = load_input(EXAMPLE);
+
+        assert_eq!(
+            instruction_stats(&instructions),
+            InstructionStats {
+                total_moves: 7,
+                max_amount: 3,
+                sources: 2,
+                destinations: 3,
+                most_used_route: Some((2, 1)),
+            }
+        );
+        assert_eq!(instruction_stats(&[]).most_used_route, None);
+    }
+
+    #[test]
+    fn test_always_buried() {
+        // [B]
+        // [A] [D]
+        // [C] [E] [F]
+        //  1   2   3
+        let stacks = vec![vec!['C', 'A', 'B'], vec!['E', 'D'], vec!['F']];
+
+        // move 1 from 2 to 3
+        // move 1 from 1 to 3
+        let instructions = vec![
+            Instruction::new(1, 2, 3).unwrap(),
+            Instruction::new(1, 1, 3).unwrap(),
+        ];
+
+        // A and E surface once D and B are moved away, C never does
+        assert_eq!(always_buried(&stacks, &instructions), vec!['C']);
+
+        // the second instruction takes more crates than stack 1 holds, so only E surfaces
+        let instructions = vec![
+            Instruction::new(1, 2, 3).unwrap(),
+            Instruction::new(4, 1, 3).unwrap(),
+        ];
+        assert_eq!(always_buried(&stacks, &instructions), vec!['C', 'A']);
+    }
+
+    #[test]
+    fn test_top_crates_trace() {
+        let (stacks, instructions) = load_input(EXAMPLE);
+
+        let expected = vec!["NDP", "DCP", "CZ", "MZ", "CMZ"];
+
+        assert_eq!(
+            top_crates_trace(stacks.clone(), &instructions, Crane::Mover9000),
+            expected
+        );
+
+        // the second instruction takes more crates than stack 1 holds after the first one
+        let instructions = load_instructions("move 1 from 2 to 1\nmove 4 from 1 to 3").unwrap();
+        assert_eq!(
+            top_crates_trace(stacks, &instructions, Crane::Mover9001),
+            vec!["NDP", "DCP"]
+        );
+    }
+
+    #[test]
+    fn test_step() {
+        let (mut stacks, instructions) = load_input(EXAMPLE);
+
+        // move 1 from 2 to 1
+        assert_eq!(
+            step(&mut stacks, &instructions, 0, Crane::Mover9000),
+            Ok("DCP".to_string())
+        );
+        assert_eq!(stacks, vec![vec!['Z', 'N', 'D'], vec!['M', 'C'], vec!['P']]);
+
+        assert_eq!(
+            step(&mut stacks, &instructions, 4, Crane::Mover9000),
+            Err(MoveError::NoInstruction(4))
+        );
+        // stack 2 only holds M and C now
+        let invalid = [
+            Instruction::new(3, 2, 1).unwrap(),
+            Instruction::new(1, 2, 4).unwrap(),
+        ];
+        assert_eq!(
+            step(&mut stacks, &invalid, 0, Crane::Mover9001),
+            Err(MoveError::NotEnoughCrates {
+                stack: 2,
+                available: 2
+            })
+        );
+        assert_eq!(
+            step(&mut stacks, &invalid, 1, Crane::Mover9001),
+            Err(MoveError::NoStack(4))
+        );
+        assert_eq!(stacks, vec![vec!['Z', 'N', 'D'], vec!['M', 'C'], vec!['P']]);
+    }
+
+    #[test]
+    fn test_throughput() {
+        let (stacks, instructions) = load_input(EXAMPLE);
+
+        // 3 and then 1 crates from stack 1, 1 and then 2 from stack 2
+        assert_eq!(throughput(&stacks, &instructions), vec![4, 3, 0]);
+
+        let impossible = [
+            Instruction::new(1, 3, 1).unwrap(),
+            Instruction::new(5, 1, 2).unwrap(),
+        ];
+        assert_eq!(throughput(&stacks, &impossible), vec![0, 0, 1]);
+    }
+
+    #[test]
+    fn test_first_empty_event() {
+        let (stacks, instructions) = load_input(EXAMPLE);
+
+        // move 3 from 1 to 3 takes Z, N and D off stack 1
+        assert_eq!(
+            first_empty_event(&stacks, &instructions, Crane::Mover9000),
+            Some((1, 1))
+        );
+
+        let stacks = vec![vec!['A', 'B'], vec![], vec!['C']];
+        let instructions = [
+            Instruction::new(1, 1, 2).unwrap(),
+            Instruction::new(1, 3, 1).unwrap(),
+        ];
+        assert_eq!(
+            first_empty_event(&stacks, &instructions, Crane::Mover9001),
+            Some((1, 3))
+        );
+        assert_eq!(
+            first_empty_event(&stacks, &instructions[..1], Crane::Mover9001),
+            None
+        );
+    }
+
+    #[test]
+    fn test_check_references() {
+        let (stacks, instructions) = load_input(EXAMPLE);
+        assert_eq!(check_references(&stacks, &instructions), Ok(()));
+
+        let instructions = [
+            Instruction::new(1, 2, 1).unwrap(),
+            Instruction::new(1, 1, 4).unwrap(),
+        ];
+        assert_eq!(
+            check_references(&stacks, &instructions),
+            Err(MoveError::NoStack(4))
+        );
+        assert_eq!(
+            check_references(
+                &stacks,
+                &[Instruction {
+                    amount: 1,
+                    from: 0,
+                    to: 1
+                }]
+            ),
+            Err(MoveError::NoStack(0))
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "The instructions don't match the stacks: NoStack(4)")]
+    fn test_solve_unknown_stack() {
+        solve("[Z] [M] [P]\n 1   2   3\n\nmove 1 from 2 to 4");
+    }
+
+    #[test]
+    fn test_simulation_log() {
+        let log = simulation_log(EXAMPLE, Crane::Mover9000).unwrap();
+
+        let lines: Vec<&str> = log.lines().collect();
+        assert_eq!(lines.len(), 5);
+        assert_eq!(lines[0], "start: ZN|MCD|P");
+        assert_eq!(lines[1], "move 1 from 2 to 1: ZND|MC|P");
+        assert_eq!(lines[2], "move 3 from 1 to 3: |MC|PDNZ");
+        assert_eq!(lines[4], "move 1 from 1 to 2: C|M|PDNZ");
+
+        assert_eq!(
+            simulation_log("[A]\n 1   2\n\nmove 2 from 1 to 2", Crane::Mover9001),
+            Err(AocError::Rearrangement {
+                instruction: 1,
+                error: MoveError::NotEnoughCrates {
+                    stack: 1,
+                    available: 1
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn test_try_rearrange() {
+        let (stacks, instructions) = load_input(EXAMPLE);
+
+        assert_eq!(
+            try_rearrange(stacks.clone(), &instructions, Crane::Mover9001),
+            Ok(rearrange(stacks.clone(), &instructions, Crane::Mover9001))
+        );
+        let instructions = load_instructions("move 1 from 2 to 1\nmove 4 from 1 to 3").unwrap();
+        assert_eq!(
+            try_rearrange(stacks, &instructions, Crane::Mover9000),
+            Err(AocError::Rearrangement {
+                instruction: 2,
+                error: MoveError::NotEnoughCrates {
+                    stack: 1,
+                    available: 3
+                }
+            })
+        );
+        assert_eq!(check_rearrangement(EXAMPLE), Ok(()));
+    }
+
+    #[test]
+    fn test_moves_to_surface() {
+        let (stacks, _) = load_input(EXAMPLE);
+
+        // C and D are on top of M
+        assert_eq!(moves_to_surface(&stacks, 2, 2), Some(2));
+        assert_eq!(moves_to_surface(&stacks, 2, 0), Some(0));
+        assert_eq!(moves_to_surface(&[vec!['A']], 1, 0), Some(0));
+    }
+
+    #[test]
+    fn test_moves_to_surface_no_crate() {
+        let (stacks, _) = load_input(EXAMPLE);
+
+        assert_eq!(moves_to_surface(&stacks, 3, 1), None);
+        assert_eq!(moves_to_surface(&stacks, 0, 0), None);
+        assert_eq!(moves_to_surface(&stacks, 4, 0), None);
+        // there's nowhere to put A
+        assert_eq!(moves_to_surface(&[vec!['B', 'A']], 1, 1), None);
+    }
+
+    #[test]
+    fn test_check() {
+        assert_eq!(check(EXAMPLE), Ok(()));
+    }
+
+    #[test]
+    fn test_check_conservation() {
+        let (stacks, instructions) = load_input(EXAMPLE);
+        let rearranged = rearrange(stacks.clone(), &instructions, Crane::Mover9001);
+        assert_eq!(check_conservation(&stacks, &rearranged), Ok(()));
+
+        let mut corrupted = rearranged.clone();
+        corrupted[0].push('X');
+        assert_eq!(
+            check_conservation(&stacks, &corrupted),
+            Err(AocError::CrateMismatch)
+        );
+
+        let mut corrupted = rearranged;
+        corrupted[2].pop();
+        assert_eq!(
+            check_conservation(&stacks, &corrupted),
+            Err(AocError::CrateMismatch)
+        );
+    }
+
+    #[test]
+    fn test_load_instructions_empty() {
+        assert_eq!(load_instructions(""), Ok(vec![]));
+        assert_eq!(load_instructions("\n  \n"), Ok(vec![]));
+        assert!(load_instructions("move 1 from 2 to 1\nmove one from 2 to 1").is_err());
+    }
+
+    #[test]
+    fn test_solve_without_instructions() {
+        let input = "    [D]    \n[N] [C]    \n[Z] [M] [P]\n 1   2   3 \n\n\n";
+
+        // without any instructions the crates on top stay where they are
+        assert_eq!(solve(input), DayResult::new("NDP", "NDP"));
+    }
+
+    #[test]
+    fn test_run_with_options() {
+        for options in 0..16 {
+            let options = RunOptions {
+                verbose: options & 1 != 0,
+                trace: options & 2 != 0,
+                time: options & 4 != 0,
+                check: options & 8 != 0,
+            };
+
+            let output = run_with_options(EXAMPLE, &options).unwrap();
+
+            assert_eq!(output.result, DayResult::new("CMZ", "MCD"), "{:?}", options);
+            assert_eq!(output.timings.parse.is_some(), options.time);
+            assert_eq!(output.timings.part_one.is_some(), options.time);
+            assert_eq!(output.timings.part_two.is_some(), options.time);
+            // the initial state and four instructions for both cranes
+            let trace_lines = if options.trace { 10 } else { 0 };
+            let verbose_lines = if options.verbose { 1 } else { 0 };
+            assert_eq!(output.traces.len(), trace_lines + verbose_lines);
+        }
+    }
+
+    #[test]
+    fn test_run_with_options_invalid() {
+        let options = RunOptions::default();
+
+        assert!(matches!(
+            run_with_options("[Z]\n 1\n\nmove one from 1 to 2", &options),
+            Err(AocError::Parse { day: 5, .. })
+        ));
+        assert!(matches!(
+            run_with_options("[Z]\n 1\n\nmove 1 from 1 to 2", &options),
+            Err(AocError::Parse { day: 5, .. })
+        ));
+        assert!(matches!(
+            run_with_options("[Z] [M]\n 1   2\n\nmove 2 from 1 to 2", &options),
+            Err(AocError::Rearrangement { instruction: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn test_run_with_options_traces() {
+        let options = RunOptions {
+            verbose: true,
+            trace: true,
+            ..RunOptions::default()
+        };
+
+        let traces = run_with_options(EXAMPLE, &options).unwrap().traces;
+
+        assert_eq!(traces[0], "7 crate-moves performed.");
+        assert_eq!(traces[1], "Mover9000 step 0: NDP");
+        assert_eq!(traces[5], "Mover9000 step 4: CMZ");
+        assert_eq!(traces[10], "Mover9001 step 4: MCD");
+    }
+
+    #[test]
+    fn test_stacks_from_columns() {
+        let columns = vec![vec!['Z', 'N'], vec!['M', 'C', 'D'], vec!['P']];
+
+        let stacks = stacks_from_columns(columns).unwrap();
+
+        let (expected, _) = load_input(EXAMPLE);
+        assert_eq!(stacks, expected);
+        assert_eq!(top_crates(&stacks), "NDP");
+    }
+
+    #[test]
+    fn test_stacks_from_columns_invalid() {
+        assert!(stacks_from_columns(vec![]).is_err());
+        // an empty stack is fine, it's only a problem when there are none
+        assert_eq!(stacks_from_columns(vec![vec![]]), Ok(vec![vec![]]));
+        assert_eq!(
+            stacks_from_columns(vec![vec!['Z'], vec!['M', ' '], vec![']']]),
+            Err(AocError::Parse {
+                day: 5,
+                problems: vec![
+                    "stack 2 has ' ', which isn't a crate label".into(),
+                    "stack 3 has ']', which isn't a crate label".into(),
+                ]
+            })
+        );
+    }
+
+    #[test]
+    fn test_instructions_between() {
+        let (from, instructions) = load_input(EXAMPLE);
+        let to = rearrange(from.clone(), &instructions, Crane::Mover9000);
+
+        let found = instructions_between(&from, &to).unwrap();
+        assert_eq!(rearrange(from.clone(), &found, Crane::Mover9000), to);
+
+        let found = instructions_between(&to, &from).unwrap();
+        assert_eq!(rearrange(to, &found, Crane::Mover9000), from);
+
+        let from = vec![vec!['A', 'B', 'A'], vec![], vec!['C', 'B'], vec!['D']];
+        let to = vec![vec!['B'], vec!['D', 'A', 'C', 'A'], vec![], vec!['B']];
+        let found = instructions_between(&from, &to).unwrap();
+        assert_eq!(rearrange(from, &found, Crane::Mover9000), to);
+    }
+
+    #[test]
+    fn test_instructions_between_two_stacks() {
+        let from = vec![vec!['A', 'B'], vec!['C']];
+
+        let to = vec![vec![], vec!['C', 'B', 'A']];
+        assert_eq!(
+            instructions_between(&from, &to),
+            Some(vec![Instruction::new(2, 1, 2).unwrap()])
+        );
+        // B can't get below A without a third stack
+        assert_eq!(
+            instructions_between(&from, &[vec!['B', 'A', 'C'], vec![]]),
+            None
+        );
+    }
+
+    #[test]
+    fn test_instructions_between_impossible() {
+        let from = vec![vec!['A', 'B'], vec![], vec!['C']];
+
+        assert_eq!(
+            instructions_between(&from, &[vec!['A', 'B'], vec![], vec!['D']]),
+            None
+        );
+        assert_eq!(
+            instructions_between(&from, &[vec!['A', 'B', 'C'], vec![]]),
+            None
+        );
+        assert_eq!(
+            instructions_between(&[vec!['A', 'B']], &[vec!['B', 'A']]),
+            None
+        );
+    }
+
+    #[test]
+    fn test_independent_batches() {
+        let instructions = load_instructions(
+            "move 1 from 1 to 2\nmove 1 from 3 to 4\nmove 2 from 2 to 3\nmove 1 from 5 to 6",
+        )
+        .unwrap();
+
+        // the first, second and fourth instruction don't share any stacks, the third has to wait
+        assert_eq!(
+            independent_batches(&instructions),
+            vec![vec![0, 1, 3], vec![2]]
+        );
+    }
+
+    #[test]
+    fn test_independent_batches_example() {
+        let (stacks, instructions) = load_input(EXAMPLE);
+
+        let batches = independent_batches(&instructions);
+
+        // every instruction of the example touches stack 1
+        assert_eq!(batches, vec![vec![0], vec![1], vec![2], vec![3]]);
+        let reordered: Vec<Instruction> = batches
+            .iter()
+            .flatten()
+            .map(|&i| instructions[i].clone())
+            .collect();
+        assert_eq!(
+            rearrange(stacks, &reordered, Crane::Mover9000),
+            stacks_from_columns(vec![vec!['C'], vec!['M'], vec!['P', 'D', 'N', 'Z']]).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_solve_cached() {
+        // only this test uses the cache, so the misses can't be counted by another test
+        let misses = || CACHE_MISSES.load(AtomicOrdering::Relaxed);
+        let expected = ("CMZ".to_string(), "MCD".to_string());
+
+        let before = misses();
+        assert_eq!(solve_cached(EXAMPLE), Ok(expected.clone()));
+        assert_eq!(misses(), before + 1);
+        assert_eq!(solve_cached(EXAMPLE), Ok(expected.clone()));
+        assert_eq!(misses(), before + 1);
+
+        clear_cache();
+        assert_eq!(solve_cached(EXAMPLE), Ok(expected));
+        assert_eq!(misses(), before + 2);
+
+        assert!(solve_cached("no stacks here").is_err());
+    }
+
+    #[test]
+    fn test_final_stack_of() {
+        let (stacks, instructions) = load_input(EXAMPLE);
+        let stacks = rearrange(stacks, &instructions, Crane::Mover9000);
+
+        //         [Z]
+        //         [N]
+        //         [D]
+        // [C] [M] [P]
+        //  1   2   3
+        assert_eq!(final_stack_of(&stacks, 'C'), Some(1));
+        assert_eq!(final_stack_of(&stacks, 'M'), Some(2));
+        assert_eq!(final_stack_of(&stacks, 'D'), Some(3));
+        assert_eq!(final_stack_of(&stacks, 'X'), None);
+        assert_eq!(final_stack_of(&[vec!['A'], vec!['B', 'A']], 'A'), Some(1));
+    }
+
+    #[test]
+    fn test_deepest_crate() {
+        let (stacks, _) = load_input(EXAMPLE);
+
+        // D and C are on top of M
+        assert_eq!(deepest_crate(&stacks), Some((2, 'M')));
+        assert_eq!(
+            deepest_crate(&[vec!['A'], vec!['B', 'C'], vec!['D', 'E']]),
+            Some((2, 'B'))
+        );
+        assert_eq!(deepest_crate(&[vec![], vec![]]), None);
+        assert_eq!(deepest_crate(&[]), None);
+    }
+
+    #[test]
+    fn test_all_stacks_sorted() {
+        assert!(all_stacks_sorted(&[
+            vec!['A', 'B', 'B', 'D'],
+            vec![],
+            vec!['Z']
+        ]));
+        assert!(!all_stacks_sorted(&[vec!['A', 'B'], vec!['C', 'A']]));
+
+        let (stacks, _) = load_input(EXAMPLE);
+        assert!(!all_stacks_sorted(&stacks));
+    }
+
+    #[test]
+    fn test_verify_numbering() {
+        assert_eq!(verify_numbering(EXAMPLE), Ok(3));
+        assert_eq!(
+            verify_numbering("[Z] [M] [P]\n 1   2   4\n\nmove 1 from 2 to 1"),
+            Err(AocError::Parse {
+                day: 5,
+                problems: vec!["the numbering row doesn't count from 1 to 3: ' 1   2   4'".into()]
+            })
+        );
+        assert!(verify_numbering("[Z] [M] [P]\n\nmove 1 from 2 to 1").is_err());
+    }
+
+    #[test]
+    fn test_crate_mover_9002() {
+        let (stacks, instructions) = load_input(EXAMPLE);
+
+        let stacks = rearrange(stacks, &instructions, Crane::Mover9002);
+
+        //         [P]
+        //         [Z]
+        //         [N]
+        // [M] [C] [D]
+        //  1   2   3
+        assert_eq!(stacks, vec![vec!['M'], vec!['C'], vec!['D', 'N', 'Z', 'P']]);
+        assert_eq!(top_crates(&stacks), "MCP");
+    }
 }
@@ -0,0 +1,113 @@
+use crate::AocError;
+use std::borrow::Cow;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Where the inputs are read from at runtime, relative to the working directory.
+pub const INPUT_DIR: &str = "input";
+
+/// The input of the given day that's bundled into the binary, if there is one.
+#[cfg(feature = "bundled-input")]
+pub fn bundled(day: u8) -> Option<&'static str> {
+    match day {
+        1 => Some(crate::day_01::INPUT),
+        2 => Some(crate::day_02::INPUT),
+        3 => Some(crate::day_03::INPUT),
+        4 => Some(crate::day_04::INPUT),
+        5 => Some(crate::day_05::INPUT),
+        _ => None,
+    }
+}
+
+/// Without the `bundled-input` feature there are no bundled inputs.
+#[cfg(not(feature = "bundled-input"))]
+pub fn bundled(_day: u8) -> Option<&'static str> {
+    None
+}
+
+/// Reads the input of the given day from the `input` directory at runtime.
+pub fn read_external(day: u8) -> io::Result<String> {
+    load_day_input(day, Path::new(INPUT_DIR))
+}
+
+/// The input of the given day, either the bundled one or the one in the `input` directory. The
+/// preferred one is used when it's there, otherwise it falls back to the other.
+pub fn for_day(day: u8, prefer_external: bool) -> Result<Cow<'static, str>, AocError> {
+    for_day_in(day, prefer_external, Path::new(INPUT_DIR))
+}
+
+fn for_day_in(day: u8, prefer_external: bool, dir: &Path) -> Result<Cow<'static, str>, AocError> {
+    let bundled = bundled(day);
+    if let (false, Some(input)) = (prefer_external, bundled) {
+        return Ok(Cow::Borrowed(input));
+    }
+    match (load_day_input(day, dir), bundled) {
+        (Ok(input), _) => Ok(Cow::Owned(input)),
+        (Err(_), Some(input)) => Ok(Cow::Borrowed(input)),
+        (Err(e), None) => Err(AocError::Io(format!(
+            "Couldn't read the input of day {}: {}",
+            day, e
+        ))),
+    }
+}
+
+/// Reads the input of the given day from `dir`, using the same `day_0N` naming as the bundled
+/// inputs in the `input` directory.
+pub fn load_day_input(day: u8, dir: &Path) -> io::Result<String> {
+    fs::read_to_string(dir.join(format!("day_{:02}", day)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    #[test]
+    fn test_load_day_input() {
+        let dir = env::temp_dir().join(format!("aoc_2022_load_day_input_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("day_01"), "1000\n2000\n\n3000").unwrap();
+
+        let loaded = load_day_input(1, &dir);
+        let missing = load_day_input(2, &dir);
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(loaded.unwrap(), "1000\n2000\n\n3000");
+        assert!(missing.is_err());
+    }
+
+    #[test]
+    fn test_for_day_external() {
+        let dir = env::temp_dir().join(format!("aoc_2022_for_day_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("day_01"), "1000\n2000\n\n3000").unwrap();
+
+        let preferred = for_day_in(1, true, &dir);
+        let not_preferred = for_day_in(1, false, &dir);
+        let missing = for_day_in(9, true, &dir);
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(preferred.unwrap(), "1000\n2000\n\n3000");
+        // the bundled input wins unless the external one is preferred
+        let expected = bundled(1).unwrap_or("1000\n2000\n\n3000");
+        assert_eq!(not_preferred.unwrap(), expected);
+        assert!(matches!(missing, Err(AocError::Io(_))));
+    }
+
+    #[cfg(feature = "bundled-input")]
+    #[test]
+    fn test_for_day_falls_back_to_bundled() {
+        let dir = env::temp_dir().join("aoc_2022_for_day_missing_dir");
+
+        assert_eq!(for_day_in(5, true, &dir).unwrap(), crate::day_05::INPUT);
+        assert_eq!(bundled(6), None);
+    }
+
+    #[test]
+    fn test_read_external() {
+        // the tests run from the crate root, where the inputs are
+        assert!(read_external(1).is_ok());
+        assert!(read_external(9).is_err());
+    }
+}
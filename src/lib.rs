@@ -0,0 +1,424 @@
+extern crate lazy_static;
+
+pub mod day_01;
+pub mod day_02;
+pub mod day_03;
+pub mod day_04;
+pub mod day_05;
+pub mod input;
+pub mod util;
+
+use std::fmt;
+use std::fmt::Display;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::Duration;
+
+/// The answers to both parts of a day's puzzle.
+#[derive(Debug, PartialEq, Clone)]
+pub struct DayResult {
+    pub part_one: String,
+    pub part_two: String,
+}
+
+impl DayResult {
+    pub fn new(part_one: impl Display, part_two: impl Display) -> DayResult {
+        DayResult {
+            part_one: part_one.to_string(),
+            part_two: part_two.to_string(),
+        }
+    }
+}
+
+/// What to do besides solving a day.
+#[derive(Debug, PartialEq, Default, Clone, Copy)]
+pub struct RunOptions {
+    /// Add a summary of how the answers came about to the traces.
+    pub verbose: bool,
+    /// Add a line per step of the solution to the traces.
+    pub trace: bool,
+    /// Measure how long parsing and both parts take.
+    pub time: bool,
+    /// Run the self-checks of the day, failing when they don't hold.
+    pub check: bool,
+}
+
+/// How long the steps of solving a day took, only measured with the `time` option.
+#[derive(Debug, PartialEq, Default, Clone)]
+pub struct Timings {
+    pub parse: Option<Duration>,
+    pub part_one: Option<Duration>,
+    pub part_two: Option<Duration>,
+}
+
+/// The answers of a day together with whatever else the `RunOptions` asked for.
+#[derive(Debug, PartialEq, Clone)]
+pub struct DayOutput {
+    pub result: DayResult,
+    pub timings: Timings,
+    pub traces: Vec<String>,
+}
+
+/// Errors found while solving a day's puzzle.
+#[derive(Debug, PartialEq)]
+pub enum AocError {
+    /// The crates after a rearrangement aren't the crates the stacks started with.
+    CrateMismatch,
+    /// The input of the day doesn't have the expected structure.
+    Parse { day: u8, problems: Vec<String> },
+    /// The input couldn't be read.
+    Io(String),
+    /// There is no solution for the day.
+    UnknownDay(u8),
+    /// Solving the day took longer than allowed.
+    TimedOut { day: u8, timeout: Duration },
+    /// Solving the day panicked.
+    Panicked { day: u8 },
+    /// It isn't clear which day the input belongs to.
+    UnknownInput,
+    /// The instruction, counting from 1, of the day 5 rearrangement can't be carried out.
+    Rearrangement {
+        instruction: usize,
+        error: day_05::MoveError,
+    },
+}
+
+impl Display for AocError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AocError::CrateMismatch => {
+                write!(f, "the rearranged stacks don't hold the same crates")
+            }
+            AocError::Parse { day, problems } => {
+                write!(f, "the input of day {} isn't valid:", day)?;
+                for problem in problems {
+                    write!(f, "\n  {}", problem)?;
+                }
+                Ok(())
+            }
+            AocError::Io(message) => write!(f, "{}", message),
+            AocError::UnknownDay(day) => write!(f, "there is no day {}", day),
+            AocError::TimedOut { day, timeout } => {
+                write!(f, "day {} didn't finish within {:?}", day, timeout)
+            }
+            AocError::Panicked { day } => write!(f, "solving day {} panicked", day),
+            AocError::UnknownInput => write!(f, "couldn't tell which day the input belongs to"),
+            AocError::Rearrangement { instruction, error } => {
+                write!(
+                    f,
+                    "instruction {} can't be carried out: {:?}",
+                    instruction, error
+                )
+            }
+        }
+    }
+}
+
+/// Guesses which day an input belongs to from its structure, using the checks of `validate_input`:
+/// blocks of numbers are day 1, two single character columns day 2, lines of letters day 3,
+/// `a-b,c-d` pairs day 4 and a drawing of stacks followed by `move` lines day 5. When the input
+/// fits more than one day, or none at all, there is no guess.
+pub fn detect_day(input: &str) -> Option<u8> {
+    if input.trim().is_empty() {
+        return None;
+    }
+    let mut days = (1..=5).filter(|&day| validate_input(day, input).is_ok());
+    match (days.next(), days.next()) {
+        (Some(day), None) => Some(day),
+        _ => None,
+    }
+}
+
+/// Solves the given day after checking its input.
+pub fn solve_day(day: u8, input: &str) -> Result<DayResult, AocError> {
+    let solve = match day {
+        1 => day_01::solve,
+        2 => day_02::solve,
+        3 => day_03::solve,
+        4 => day_04::solve,
+        5 => day_05::solve,
+        _ => return Err(AocError::UnknownDay(day)),
+    };
+    check_input(day, input)?;
+    Ok(solve(input))
+}
+
+/// Solves the given day on a worker thread, giving up when it takes longer than the timeout.
+///
+/// This is a soft timeout: a thread can't be killed, so it only stops waiting for the answers and
+/// the worker keeps running in the background until it's done.
+pub fn run_with_timeout(day: u8, input: &str, timeout: Duration) -> Result<DayResult, AocError> {
+    let input = input.to_string();
+    run_on_worker(day, timeout, move || solve_day(day, &input))
+}
+
+fn run_on_worker(
+    day: u8,
+    timeout: Duration,
+    solve: impl FnOnce() -> Result<DayResult, AocError> + Send + 'static,
+) -> Result<DayResult, AocError> {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        // after a timeout nobody is listening anymore, which is fine
+        let _ = sender.send(solve());
+    });
+
+    match receiver.recv_timeout(timeout) {
+        Ok(result) => result,
+        Err(RecvTimeoutError::Timeout) => Err(AocError::TimedOut { day, timeout }),
+        // the worker dropped the sender without sending anything, which only happens on a panic
+        Err(RecvTimeoutError::Disconnected) => Err(AocError::Panicked { day }),
+    }
+}
+
+/// Runs the self-checks of the given day on an input. Days without any self-checks always pass.
+pub fn check_solution(day: u8, input: &str) -> Result<(), AocError> {
+    match day {
+        5 => day_05::check(input),
+        _ => Ok(()),
+    }
+}
+
+/// Like `validate_input`, but with the problems as an error, to check an input before solving it.
+/// For day 5 it also checks that every instruction can be carried out.
+pub fn check_input(day: u8, input: &str) -> Result<(), AocError> {
+    validate_input(day, input).map_err(|problems| AocError::Parse { day, problems })?;
+    match day {
+        5 => day_05::check_rearrangement(input),
+        _ => Ok(()),
+    }
+}
+
+/// Runs the structural checks of the given day on an input, returning the problems found.
+pub fn validate_input(day: u8, input: &str) -> Result<(), Vec<String>> {
+    let problems = match day {
+        1 => day_01::validate(input),
+        2 => day_02::validate(input),
+        3 => day_03::validate(input),
+        4 => day_04::validate(input),
+        5 => day_05::validate(input),
+        _ => vec![format!("there is no day {}", day)],
+    };
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(problems)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_input_day_01() {
+        assert_eq!(validate_input(1, "1000\n2000\n\n3000"), Ok(()));
+        assert_eq!(
+            validate_input(1, "1000\ntwo\n\n3000"),
+            Err(vec!["line 2: 'two' is not a number".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_validate_input_day_02() {
+        assert_eq!(validate_input(2, "A Y\nB X\nC Z"), Ok(()));
+        assert_eq!(
+            validate_input(2, "A Y\nBX\nC Z"),
+            Err(vec![
+                "line 2: 'BX' isn't two single character columns".to_string()
+            ])
+        );
+        assert_eq!(
+            validate_input(2, "A Y\nA W\nD X"),
+            Err(vec![
+                "line 2: 'A W' has 'W', which isn't one of A-C or X-Z".to_string(),
+                "line 3: 'D X' has 'D', which isn't one of A-C or X-Z".to_string(),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_validate_input_day_03() {
+        let group =
+            "vJrwpWtwJgWrhcsFMMfFFhFp\njqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL\nPmmdzqPrVvPwwTWBwg";
+        assert_eq!(validate_input(3, group), Ok(()));
+        assert_eq!(
+            validate_input(3, "vJrwpWtwJgWrhcsFMMfFFhF"),
+            Err(vec![
+                "line 1: 'vJrwpWtwJgWrhcsFMMfFFhF' isn't an even amount of letters".to_string()
+            ])
+        );
+        assert_eq!(
+            validate_input(3, "ab\naa"),
+            Err(vec![
+                "line 1: 'ab' has no item in both compartments".to_string()
+            ])
+        );
+        // a single rucksack has all of its items in common with itself
+        assert_eq!(
+            validate_input(3, &format!("{}\nvJrwpWtwJgWrhcsFMMfFFhFp", group)),
+            Err(vec![
+                "lines 4-4: the group doesn't share exactly one item".to_string()
+            ])
+        );
+    }
+
+    #[test]
+    fn test_validate_input_day_04() {
+        assert_eq!(validate_input(4, "2-4,6-8\n2-3,4-5"), Ok(()));
+        assert_eq!(
+            validate_input(4, "2-4,6-8\n2-3;4-5"),
+            Err(vec![
+                "line 2: '2-3;4-5' isn't a pair like 'a-b,c-d'".to_string()
+            ])
+        );
+    }
+
+    #[test]
+    fn test_validate_input_day_05() {
+        let stacks = "    [D]\n[N] [C]\n[Z] [M] [P]\n 1   2   3";
+        assert_eq!(
+            validate_input(5, &format!("{}\n\nmove 1 from 2 to 1", stacks)),
+            Ok(())
+        );
+        assert_eq!(
+            validate_input(5, &format!("{}\n\nmove one from 2 to 1", stacks)),
+            Err(vec![
+                "line 6: 'move one from 2 to 1' isn't an instruction".to_string()
+            ])
+        );
+        assert_eq!(
+            validate_input(5, "[Z] [M] [P]\n\nmove 1 from 2 to 1"),
+            Err(vec![
+                "the stacks don't end in a numbering row: '[Z] [M] [P]'".to_string()
+            ])
+        );
+        assert_eq!(
+            validate_input(5, &format!("{}\n\nmove 1 from 2 to 4", stacks)),
+            Err(vec![
+                "an instruction refers to stack 4, but there are only 3 stacks".to_string()
+            ])
+        );
+        assert!(validate_input(5, stacks).is_err());
+        assert!(validate_input(5, &format!("{}\n", stacks)).is_err());
+        assert_eq!(validate_input(5, &format!("{}\n\n", stacks)), Ok(()));
+    }
+
+    #[test]
+    fn test_validate_input_day_05_separators() {
+        let stacks = "    [D]\n[N] [C]\n[Z] [M] [P]\n 1   2   3";
+        for separator in ["\r\n\r\n", "\n  \n", "\n\n\n"] {
+            let input = format!("{}{}move 1 from 2 to 1", stacks, separator);
+            assert_eq!(validate_input(5, &input), Ok(()));
+            assert_eq!(solve_day(5, &input), Ok(DayResult::new("DCP", "DCP")));
+        }
+        // the blank lines count towards the line number
+        assert_eq!(
+            validate_input(5, &format!("{}\n \n\nmove one from 2 to 1", stacks)),
+            Err(vec![
+                "line 7: 'move one from 2 to 1' isn't an instruction".to_string()
+            ])
+        );
+    }
+
+    #[test]
+    fn test_validate_input_unknown_day() {
+        assert!(validate_input(26, "").is_err());
+    }
+
+    #[test]
+    fn test_check_solution() {
+        let stacks = "    [D]\n[N] [C]\n[Z] [M] [P]\n 1   2   3";
+        assert_eq!(
+            check_solution(5, &format!("{}\n\nmove 1 from 2 to 1", stacks)),
+            Ok(())
+        );
+        assert_eq!(check_solution(1, "1000\n2000\n\n3000"), Ok(()));
+    }
+
+    #[test]
+    fn test_check_input() {
+        assert_eq!(check_input(1, "1000\n2000\n\n3000"), Ok(()));
+        let error = check_input(1, "1000\ntwo").unwrap_err();
+        assert_eq!(
+            error,
+            AocError::Parse {
+                day: 1,
+                problems: vec!["line 2: 'two' is not a number".to_string()]
+            }
+        );
+        assert_eq!(
+            error.to_string(),
+            "the input of day 1 isn't valid:\n  line 2: 'two' is not a number"
+        );
+    }
+
+    #[test]
+    fn test_solve_day() {
+        assert_eq!(solve_day(2, "A Y\nB X\nC Z"), Ok(DayResult::new(15, 12)));
+        assert!(matches!(
+            solve_day(2, "A Y\nBX"),
+            Err(AocError::Parse { day: 2, .. })
+        ));
+        for input in ["", "\n\n"] {
+            assert_eq!(solve_day(1, input), Ok(DayResult::new(0, 0)));
+        }
+        for (day, input) in [(2, "A W"), (2, "D X"), (3, "ab")] {
+            assert!(matches!(solve_day(day, input), Err(AocError::Parse { .. })));
+        }
+        assert_eq!(solve_day(26, ""), Err(AocError::UnknownDay(26)));
+        assert_eq!(
+            solve_day(
+                5,
+                "[Z] [M]\n 1   2\n\nmove 1 from 1 to 2\nmove 2 from 1 to 2"
+            ),
+            Err(AocError::Rearrangement {
+                instruction: 2,
+                error: day_05::MoveError::NotEnoughCrates {
+                    stack: 1,
+                    available: 0
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn test_run_with_timeout() {
+        assert_eq!(
+            run_with_timeout(2, "A Y\nB X\nC Z", Duration::from_secs(10)),
+            Ok(DayResult::new(15, 12))
+        );
+        assert_eq!(
+            run_with_timeout(26, "", Duration::from_secs(10)),
+            Err(AocError::UnknownDay(26))
+        );
+    }
+
+    #[test]
+    fn test_run_on_worker_panicked() {
+        let result = run_on_worker(5, Duration::from_secs(10), || panic!("out of crates"));
+
+        assert_eq!(result, Err(AocError::Panicked { day: 5 }));
+        assert_eq!(result.unwrap_err().to_string(), "solving day 5 panicked");
+    }
+
+    #[test]
+    fn test_detect_day() {
+        let examples = [
+            "1000\n2000\n3000\n\n4000\n\n5000\n6000\n\n7000\n8000\n9000\n\n10000",
+            "A Y\nB X\nC Z",
+            "vJrwpWtwJgWrhcsFMMfFFhFp\njqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL\nPmmdzqPrVvPwwTWBwg",
+            "2-4,6-8\n2-3,4-5\n5-7,7-9\n2-8,3-7\n6-6,4-6\n2-6,4-8",
+            "    [D]    \n[N] [C]    \n[Z] [M] [P]\n 1   2   3 \n\nmove 1 from 2 to 1",
+        ];
+        for (day, example) in (1..).zip(examples) {
+            assert_eq!(detect_day(example), Some(day));
+        }
+    }
+
+    #[test]
+    fn test_detect_day_ambiguous() {
+        assert_eq!(detect_day(""), None);
+        assert_eq!(detect_day("hello world"), None);
+    }
+}
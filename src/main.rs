@@ -1,45 +1,767 @@
-extern crate lazy_static;
-
-use std::collections::HashMap;
+use advent_of_code_2022::input::{self, load_day_input};
+use advent_of_code_2022::util::split_on_blank_line;
+use advent_of_code_2022::{check_input, check_solution, detect_day, AocError, DayResult};
+use std::borrow::Cow;
+use std::collections::BTreeMap;
 use std::env;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::iter;
+use std::path::PathBuf;
+use std::process;
+use std::time::{Duration, Instant};
+
+const USAGE: &str = "Usage: advent_of_code_2022 <command> [days...] [options]
+
+Commands:
+    run       run the given days and print their answers
+    time      solve the given days and print a table of the answers and how long they took
+    bench     solve the given days repeatedly and print the average duration
+    list      list the available days
+    validate  check the inputs of the given days for structural problems
+    info      print the size of the inputs of the given days and what's in them
+    auto      run the day the `--input` file looks like it belongs to
+
+Days are given by number, e.g. `run 1 5`. Without any days, or with `all`, all of them are used.
+When the command is left out the days are run, e.g. `5 --quiet`.
+The exit code is 1 when an input isn't valid or a check fails, and 2 when an input can't be read.
+
+Options:
+    --input <file>      read the input from `<file>` instead of the bundled one
+    --input-dir <dir>   read the inputs from `<dir>/day_0N` instead of the bundled ones
+    --quiet             only print the answers, one per line
+    --format <format>   print the answers as `text` (the default), `json` or `csv`
+    --time              finish with a table of the answers and how long they took
+    --check             run the self-checks of the days after solving them";
+
+const BENCH_ITERATIONS: u32 = 100;
 
 fn main() {
-    let mut args = env::args();
-    args.next();
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    let command = match parse_args(&args) {
+        Ok(command) => command,
+        Err(message) => {
+            eprintln!("{}\n\n{}", message, USAGE);
+            process::exit(1);
+        }
+    };
 
     let modules = create_modules();
+    if let Err(e) = run_command(command, &modules) {
+        eprintln!("{}", e);
+        process::exit(exit_code(&e));
+    }
+}
+
+fn run_command(command: Command, modules: &Modules) -> Result<(), AocError> {
+    match command {
+        Command::List => {
+            for (day, module) in modules.iter() {
+                println!("{:>2}: {}", day, module.name);
+            }
+        }
+        Command::Run(options) => {
+            let mut results = BTreeMap::new();
+            if options.format == OutputFormat::Csv {
+                println!("day,part_one,part_two");
+            }
+            for (module, input) in modules.select(&options)? {
+                check_input(module.day, &input)?;
+                if options.quiet {
+                    println!("{}", quiet_output(&(module.solve)(&input)));
+                } else if options.format != OutputFormat::Text {
+                    let result = (module.solve)(&input);
+                    println!("{}", format_result(module.day, &result, options.format));
+                } else {
+                    (module.run)(&input, &mut io::stdout()).map_err(|e| {
+                        AocError::Io(format!(
+                            "Couldn't write the answers of {}: {}",
+                            module.name, e
+                        ))
+                    })?;
+                }
+                if options.time {
+                    results.insert(module.day, time_solve(module, &input));
+                }
+                if options.check {
+                    check_solution(module.day, &input)?;
+                }
+            }
+            if options.time {
+                print_summary(&results);
+            }
+        }
+        Command::Time(options) => {
+            let mut results = BTreeMap::new();
+            for (module, input) in modules.select(&options)? {
+                check_input(module.day, &input)?;
+                results.insert(module.day, time_solve(module, &input));
+                if options.check {
+                    check_solution(module.day, &input)?;
+                }
+            }
+            print_summary(&results);
+        }
+        Command::Bench(options) => {
+            for (module, input) in modules.select(&options)? {
+                check_input(module.day, &input)?;
+                let start = Instant::now();
+                for _ in 0..BENCH_ITERATIONS {
+                    (module.solve)(&input);
+                }
+                let average = start.elapsed() / BENCH_ITERATIONS;
+                println!(
+                    "{} took {:?} on average over {} runs",
+                    module.name, average, BENCH_ITERATIONS
+                );
+            }
+        }
+        Command::Auto(mut options) => {
+            let file = options
+                .input
+                .as_ref()
+                .expect("The auto command needs an input");
+            let input = fs::read_to_string(file)
+                .map_err(|e| AocError::Io(format!("Couldn't read {}: {}", file.display(), e)))?;
+            let day = detect_day(&input).ok_or(AocError::UnknownInput)?;
+            options.days = vec![day];
+            return run_command(Command::Run(options), modules);
+        }
+        Command::Validate(options) => {
+            for (module, input) in modules.select(&options)? {
+                check_input(module.day, &input)?;
+                println!("{}: ok", module.name);
+            }
+        }
+        Command::Info(options) => {
+            for (module, input) in modules.select(&options)? {
+                println!("{}: {}", module.name, input_info(module.day, &input));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The size of an input, and for the days with sections in their inputs what's in them.
+#[derive(Debug, PartialEq)]
+struct InputInfo {
+    lines: usize,
+    bytes: usize,
+    structure: Option<String>,
+}
+
+impl fmt::Display for InputInfo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} lines, {} bytes", self.lines, self.bytes)?;
+        if let Some(structure) = &self.structure {
+            write!(f, ", {}", structure)?;
+        }
+        Ok(())
+    }
+}
+
+fn input_info(day: u8, input: &str) -> InputInfo {
+    let structure = match day {
+        1 => {
+            let elves = day_01::load_calories_signed(input);
+            let items: usize = elves.iter().map(Vec::len).sum();
+            Some(format!("{} elves, {} items", elves.len(), items))
+        }
+        5 => day_05::verify_numbering(input).ok().and_then(|stacks| {
+            let instructions = split_on_blank_line(input)
+                .get(1)
+                .map_or(Ok(vec![]), |section| day_05::load_instructions(section))
+                .ok()?;
+            Some(format!(
+                "{} stacks, {} instructions",
+                stacks,
+                instructions.len()
+            ))
+        }),
+        _ => None,
+    };
+    InputInfo {
+        lines: input.lines().count(),
+        bytes: input.len(),
+        structure,
+    }
+}
+
+/// The exit code for an error: 2 when the input couldn't be read, 1 when the input or the answers
+/// are wrong or a day couldn't be solved at all.
+fn exit_code(error: &AocError) -> i32 {
+    match error {
+        AocError::Parse { .. }
+        | AocError::CrateMismatch
+        | AocError::UnknownDay(_)
+        | AocError::TimedOut { .. }
+        | AocError::Panicked { .. }
+        | AocError::UnknownInput
+        | AocError::Rearrangement { .. } => 1,
+        AocError::Io(_) => 2,
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Command {
+    Run(Options),
+    Time(Options),
+    Bench(Options),
+    List,
+    Validate(Options),
+    Auto(Options),
+    Info(Options),
+}
+
+/// How the `run` command prints the answers.
+#[derive(Debug, PartialEq, Copy, Clone, Default)]
+enum OutputFormat {
+    /// The sentences of the days themselves.
+    #[default]
+    Text,
+    /// A JSON object per day.
+    Json,
+    /// A row per day, after a header row.
+    Csv,
+}
+
+#[derive(Debug, PartialEq, Default)]
+struct Options {
+    days: Vec<u8>,
+    input: Option<PathBuf>,
+    input_dir: Option<PathBuf>,
+    quiet: bool,
+    format: OutputFormat,
+    time: bool,
+    check: bool,
+}
+
+fn parse_args(args: &[String]) -> Result<Command, String> {
+    let Some((command, rest)) = args.split_first() else {
+        return Err("No command given".into());
+    };
+
+    match command.as_str() {
+        "run" => Ok(Command::Run(parse_options(rest)?)),
+        "time" => Ok(Command::Time(parse_options(rest)?)),
+        "bench" => Ok(Command::Bench(parse_options(rest)?)),
+        "validate" => Ok(Command::Validate(parse_options(rest)?)),
+        "info" => Ok(Command::Info(parse_options(rest)?)),
+        "auto" => {
+            let options = parse_options(rest)?;
+            if options.input.is_none() {
+                return Err("The auto command needs an --input file".into());
+            }
+            if !options.days.is_empty() {
+                return Err("The auto command finds the day itself".into());
+            }
+            Ok(Command::Auto(options))
+        }
+        "list" if rest.is_empty() => Ok(Command::List),
+        "list" => Err("The list command doesn't take any arguments".into()),
+        c if c == "all" || c.starts_with("--") || c.parse::<u8>().is_ok() => {
+            Ok(Command::Run(parse_options(args)?))
+        }
+        c => Err(format!("Unknown command: '{}'", c)),
+    }
+}
+
+fn parse_options(args: &[String]) -> Result<Options, String> {
+    let mut options = Options::default();
+    let mut args = args.iter();
     while let Some(arg) = args.next() {
-        modules.run(&arg);
+        match arg.as_str() {
+            "--input" => {
+                let Some(file) = args.next() else {
+                    return Err("The --input option needs a file".into());
+                };
+                options.input = Some(PathBuf::from(file));
+            }
+            "--input-dir" => {
+                let Some(dir) = args.next() else {
+                    return Err("The --input-dir option needs a directory".into());
+                };
+                options.input_dir = Some(PathBuf::from(dir));
+            }
+            "--format" => {
+                options.format = match args.next().map(String::as_str) {
+                    Some("text") => OutputFormat::Text,
+                    Some("json") => OutputFormat::Json,
+                    Some("csv") => OutputFormat::Csv,
+                    Some(format) => return Err(format!("Unknown format: '{}'", format)),
+                    None => return Err("The --format option needs a format".into()),
+                };
+            }
+            "--quiet" => options.quiet = true,
+            "--time" => options.time = true,
+            "--check" => options.check = true,
+            // no days selects all of them
+            "all" => {}
+            day => {
+                let day = day
+                    .parse()
+                    .map_err(|_| format!("Expected a day number, got: '{}'", day))?;
+                options.days.push(day);
+            }
+        }
+    }
+    if options.quiet && options.format != OutputFormat::Text {
+        return Err("The --quiet option only goes with the text format".into());
+    }
+    Ok(options)
+}
+
+/// The answers of a day in the given format. The text format is a plain sentence here, when running
+/// a day it describes the answers itself.
+fn format_result(day: u8, result: &DayResult, format: OutputFormat) -> String {
+    match format {
+        OutputFormat::Text => format!(
+            "Day {}: the answer to part one is {} and to part two {}",
+            day, result.part_one, result.part_two
+        ),
+        OutputFormat::Json => format!(
+            "{{\"day\":{},\"part_one\":{},\"part_two\":{}}}",
+            day,
+            json_string(&result.part_one),
+            json_string(&result.part_two)
+        ),
+        OutputFormat::Csv => format!(
+            "{},{},{}",
+            day,
+            csv_field(&result.part_one),
+            csv_field(&result.part_two)
+        ),
+    }
+}
+
+fn json_string(value: &str) -> String {
+    let mut quoted = String::from("\"");
+    for c in value.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            c if c.is_control() => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Quotes the field when it would otherwise break the row.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// The answers of a day as printed in quiet mode: only the values, one per line.
+fn quiet_output(result: &DayResult) -> String {
+    format!("{}\n{}", result.part_one, result.part_two)
+}
+
+fn time_solve(module: &Module, input: &str) -> (DayResult, Duration) {
+    let start = Instant::now();
+    let result = (module.solve)(input);
+    (result, start.elapsed())
+}
+
+fn print_summary(results: &BTreeMap<u8, (DayResult, Duration)>) {
+    println!("{}", format_summary(results));
+}
+
+/// Formats the results as a table with a row per day, with the columns aligned.
+fn format_summary(results: &BTreeMap<u8, (DayResult, Duration)>) -> String {
+    let header = ["day", "part1", "part2", "time"].map(String::from);
+    let rows: Vec<[String; 4]> = results
+        .iter()
+        .map(|(day, (result, duration))| {
+            [
+                day.to_string(),
+                result.part_one.clone(),
+                result.part_two.clone(),
+                format!("{:?}", duration),
+            ]
+        })
+        .collect();
+
+    let mut widths = [0; 4];
+    for row in iter::once(&header).chain(&rows) {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
     }
+
+    iter::once(&header)
+        .chain(&rows)
+        .map(|[day, part_one, part_two, time]| {
+            format!(
+                "{:>day_width$} | {:<part_one_width$} | {:<part_two_width$} | {}",
+                day,
+                part_one,
+                part_two,
+                time,
+                day_width = widths[0],
+                part_one_width = widths[1],
+                part_two_width = widths[2],
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
 }
 
-type Run = fn();
+type Run = fn(&str, &mut dyn io::Write) -> io::Result<()>;
+type Solve = fn(&str) -> DayResult;
+
+struct Module {
+    day: u8,
+    name: &'static str,
+    run: Run,
+    solve: Solve,
+}
 
 struct Modules {
-    runners: HashMap<String, Run>,
+    modules: BTreeMap<u8, Module>,
 }
 
 impl Modules {
-    fn add_module(&mut self, name: String, func: Run) {
-        self.runners.insert(name, func);
+    fn add_module(&mut self, day: u8, module: Module) {
+        self.modules.insert(day, module);
+    }
+
+    fn iter(&self) -> impl Iterator<Item = (&u8, &Module)> {
+        self.modules.iter()
     }
 
-    fn run(&self, name: &String) {
-        let runner = self.runners.get(name).unwrap();
-        runner();
+    /// Returns the modules for the selected days, or all of them when no days are selected,
+    /// together with the input they should be run on.
+    fn select(&self, options: &Options) -> Result<Vec<(&Module, Cow<'static, str>)>, AocError> {
+        let days: Vec<u8> = if options.days.is_empty() {
+            self.modules.keys().copied().collect()
+        } else {
+            options.days.clone()
+        };
+        days.iter()
+            .map(|day| {
+                let module = self.modules.get(day).ok_or(AocError::UnknownDay(*day))?;
+                let input = match (&options.input, &options.input_dir) {
+                    (Some(file), _) => fs::read_to_string(file).map(Cow::Owned),
+                    (None, Some(dir)) => load_day_input(*day, dir).map(Cow::Owned),
+                    (None, None) => {
+                        return input::for_day(*day, false).map(|input| (module, input))
+                    }
+                };
+                let input = input.map_err(|e| {
+                    AocError::Io(format!("Couldn't read the input of day {}: {}", day, e))
+                })?;
+                Ok((module, input))
+            })
+            .collect()
     }
 }
 
 macro_rules! modules {
-    ($($mod:ident,)*) => {
-        $( mod $mod; )*
+    ($($day:literal => $mod:ident,)*) => {
+        $( use advent_of_code_2022::$mod; )*
 
         fn create_modules() -> Modules {
-            let mut modules = Modules { runners: HashMap::new() };
-            $( modules.add_module(stringify!($mod).to_string(), $mod::run); )*
+            let mut modules = Modules { modules: BTreeMap::new() };
+            $(
+                modules.add_module($day, Module {
+                    day: $day,
+                    name: stringify!($mod),
+                    run: $mod::run_with_input,
+                    solve: $mod::solve,
+                });
+            )*
             modules
         }
     };
 }
 
-modules![day_01, day_02, day_03, day_04, day_05,];
+modules![
+    1 => day_01,
+    2 => day_02,
+    3 => day_03,
+    4 => day_04,
+    5 => day_05,
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_args_run() {
+        assert_eq!(
+            parse_args(&args(&["run", "1", "5"])),
+            Ok(Command::Run(Options {
+                days: vec![1, 5],
+                ..Options::default()
+            }))
+        );
+        assert_eq!(
+            parse_args(&args(&["run"])),
+            Ok(Command::Run(Options::default()))
+        );
+    }
+
+    #[test]
+    fn test_parse_args_time_and_bench() {
+        assert_eq!(
+            parse_args(&args(&["time", "3"])),
+            Ok(Command::Time(Options {
+                days: vec![3],
+                ..Options::default()
+            }))
+        );
+        assert_eq!(
+            parse_args(&args(&["bench", "04"])),
+            Ok(Command::Bench(Options {
+                days: vec![4],
+                ..Options::default()
+            }))
+        );
+    }
+
+    #[test]
+    fn test_parse_args_input_dir() {
+        assert_eq!(
+            parse_args(&args(&["run", "--input-dir", "other", "2"])),
+            Ok(Command::Run(Options {
+                days: vec![2],
+                input_dir: Some(PathBuf::from("other")),
+                ..Options::default()
+            }))
+        );
+        assert!(parse_args(&args(&["run", "2", "--input-dir"])).is_err());
+    }
+
+    #[test]
+    fn test_parse_args_list() {
+        assert_eq!(parse_args(&args(&["list"])), Ok(Command::List));
+        assert!(parse_args(&args(&["list", "1"])).is_err());
+    }
+
+    #[test]
+    fn test_parse_args_errors() {
+        assert!(parse_args(&args(&[])).is_err());
+        assert!(parse_args(&args(&["jump"])).is_err());
+        assert!(parse_args(&args(&["run", "day_01"])).is_err());
+    }
+
+    #[test]
+    fn test_parse_args_validate() {
+        assert_eq!(
+            parse_args(&args(&["validate", "5", "--input", "f"])),
+            Ok(Command::Validate(Options {
+                days: vec![5],
+                input: Some(PathBuf::from("f")),
+                ..Options::default()
+            }))
+        );
+    }
+
+    #[test]
+    fn test_parse_args_auto() {
+        assert_eq!(
+            parse_args(&args(&["auto", "--input", "f"])),
+            Ok(Command::Auto(Options {
+                input: Some(PathBuf::from("f")),
+                ..Options::default()
+            }))
+        );
+        assert!(parse_args(&args(&["auto"])).is_err());
+        assert!(parse_args(&args(&["auto", "2", "--input", "f"])).is_err());
+    }
+
+    #[test]
+    fn test_parse_args_info() {
+        assert_eq!(
+            parse_args(&args(&["info", "5", "--input", "f"])),
+            Ok(Command::Info(Options {
+                days: vec![5],
+                input: Some(PathBuf::from("f")),
+                ..Options::default()
+            }))
+        );
+    }
+
+    #[test]
+    fn test_input_info() {
+        let info = input_info(
+            1,
+            "1000\n2000\n3000\n\n4000\n\n5000\n6000\n\n7000\n8000\n9000\n\n10000",
+        );
+        assert_eq!(info.to_string(), "14 lines, 54 bytes, 5 elves, 10 items");
+
+        assert_eq!(
+            input_info(2, "A Y\nB X\nC Z").to_string(),
+            "3 lines, 11 bytes"
+        );
+        let rucksacks = "vJrwpWtwJgWrhcsFMMfFFhFp\n\
+                         jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL\n\
+                         PmmdzqPrVvPwwTWBwg\n\
+                         wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn\n\
+                         ttgJtRGJQctTZtZT\n\
+                         CrZsJsPPZsGzwwsLwLmpwMDw";
+        assert_eq!(
+            input_info(3, rucksacks),
+            InputInfo {
+                lines: 6,
+                bytes: 149,
+                structure: None
+            }
+        );
+        assert_eq!(
+            input_info(4, "2-4,6-8\n2-3,4-5\n5-7,7-9\n2-8,3-7\n6-6,4-6\n2-6,4-8").to_string(),
+            "6 lines, 47 bytes"
+        );
+
+        let info = input_info(
+            5,
+            "    [D]    \n[N] [C]    \n[Z] [M] [P]\n 1   2   3 \n\nmove 1 from 2 to 1\n\
+             move 3 from 1 to 3\nmove 2 from 2 to 1\nmove 1 from 1 to 2",
+        );
+        assert_eq!(info.lines, 9);
+        assert_eq!(info.structure.as_deref(), Some("3 stacks, 4 instructions"));
+    }
+
+    #[test]
+    fn test_parse_args_without_command() {
+        assert_eq!(
+            parse_args(&args(&["5", "--quiet"])),
+            Ok(Command::Run(Options {
+                days: vec![5],
+                quiet: true,
+                ..Options::default()
+            }))
+        );
+        assert_eq!(
+            parse_args(&args(&["--quiet"])),
+            Ok(Command::Run(Options {
+                quiet: true,
+                ..Options::default()
+            }))
+        );
+    }
+
+    #[test]
+    fn test_parse_args_check() {
+        assert_eq!(
+            parse_args(&args(&["time", "5", "--check"])),
+            Ok(Command::Time(Options {
+                days: vec![5],
+                check: true,
+                ..Options::default()
+            }))
+        );
+    }
+
+    #[test]
+    fn test_parse_args_format() {
+        assert_eq!(
+            parse_args(&args(&["run", "5", "--format", "json"])),
+            Ok(Command::Run(Options {
+                days: vec![5],
+                format: OutputFormat::Json,
+                ..Options::default()
+            }))
+        );
+        assert!(parse_args(&args(&["run", "--format", "xml"])).is_err());
+        assert!(parse_args(&args(&["run", "--format"])).is_err());
+        assert!(parse_args(&args(&["run", "--format", "csv", "--quiet"])).is_err());
+    }
+
+    #[test]
+    fn test_format_result() {
+        let result = DayResult::new("CMZ", "MCD");
+
+        assert_eq!(
+            format_result(5, &result, OutputFormat::Text),
+            "Day 5: the answer to part one is CMZ and to part two MCD"
+        );
+        assert_eq!(
+            format_result(5, &result, OutputFormat::Json),
+            r#"{"day":5,"part_one":"CMZ","part_two":"MCD"}"#
+        );
+        assert_eq!(format_result(5, &result, OutputFormat::Csv), "5,CMZ,MCD");
+    }
+
+    #[test]
+    fn test_format_result_escaping() {
+        let result = DayResult::new("a,\"b\"", "c\\d\n");
+
+        assert_eq!(
+            format_result(1, &result, OutputFormat::Json),
+            r#"{"day":1,"part_one":"a,\"b\"","part_two":"c\\d\u000a"}"#
+        );
+        assert_eq!(
+            format_result(1, &result, OutputFormat::Csv),
+            "1,\"a,\"\"b\"\"\",\"c\\d\n\""
+        );
+    }
+
+    #[test]
+    fn test_exit_code() {
+        let parse_error = AocError::Parse {
+            day: 1,
+            problems: vec!["line 2: 'two' is not a number".into()],
+        };
+        assert_eq!(exit_code(&parse_error), 1);
+        assert_eq!(exit_code(&AocError::CrateMismatch), 1);
+        assert_eq!(exit_code(&AocError::Io("Couldn't read".into())), 2);
+        let rearrangement = AocError::Rearrangement {
+            instruction: 1,
+            error: day_05::MoveError::NotEnoughCrates {
+                stack: 1,
+                available: 0,
+            },
+        };
+        assert_eq!(exit_code(&rearrangement), 1);
+        assert_eq!(exit_code(&AocError::UnknownDay(26)), 1);
+    }
+
+    #[test]
+    fn test_quiet_output() {
+        let result = DayResult::new("CMZ", "MCD");
+
+        assert_eq!(quiet_output(&result), "CMZ\nMCD");
+    }
+
+    #[test]
+    fn test_parse_args_all_time() {
+        assert_eq!(
+            parse_args(&args(&["all", "--time"])),
+            Ok(Command::Run(Options {
+                time: true,
+                ..Options::default()
+            }))
+        );
+    }
+
+    #[test]
+    fn test_format_summary() {
+        let mut results = BTreeMap::new();
+        results.insert(1, (DayResult::new(24000, 45000), Duration::from_millis(1)));
+        results.insert(
+            5,
+            (DayResult::new("CMZ", "MCD"), Duration::from_micros(250)),
+        );
+
+        let expected = "\
+day | part1 | part2 | time
+  1 | 24000 | 45000 | 1ms
+  5 | CMZ   | MCD   | 250µs";
+
+        assert_eq!(format_summary(&results), expected);
+    }
+}
@@ -2,8 +2,10 @@
 /// 
 /// Problem text
 
-const INPUT: &str = include_str!("../input/day_xx");
+#[cfg(feature = "bundled-input")]
+pub const INPUT: &str = include_str!("../input/day_xx");
 
+#[cfg(feature = "bundled-input")]
 pub fn run() {
     println!("Not implemented yet");
     unimplemented!();
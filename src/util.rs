@@ -0,0 +1,78 @@
+/// A simple, stable hash of an input (64-bit FNV-1a), unlike `DefaultHasher` it doesn't change
+/// between Rust versions, so it can be used to recognise an input across runs.
+pub fn input_hash(input: &str) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+    const PRIME: u64 = 0x100000001b3;
+
+    input.bytes().fold(OFFSET_BASIS, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(PRIME)
+    })
+}
+
+/// Splits the input into blocks separated by one or more blank lines, for `\n` as well as `\r\n`
+/// line endings. A line of only spaces or tabs counts as blank too. The blocks don't include the
+/// line breaks around them, but any indentation of their first line is kept.
+pub fn split_on_blank_line(input: &str) -> Vec<&str> {
+    let mut blocks = Vec::new();
+    let mut block_start = None;
+    let mut block_end = 0;
+    let mut offset = 0;
+    for line in input.split_inclusive('\n') {
+        let content = line.trim_end_matches(['\n', '\r']);
+        if content.trim().is_empty() {
+            if let Some(start) = block_start.take() {
+                blocks.push(&input[start..block_end]);
+            }
+        } else {
+            block_start.get_or_insert(offset);
+            block_end = offset + content.len();
+        }
+        offset += line.len();
+    }
+    if let Some(start) = block_start {
+        blocks.push(&input[start..block_end]);
+    }
+    blocks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_input_hash() {
+        // reference values of 64-bit FNV-1a
+        assert_eq!(input_hash(""), 0xcbf29ce484222325);
+        assert_eq!(input_hash("a"), 0xaf63dc4c8601ec8c);
+        assert_eq!(input_hash("foobar"), 0x85944171f73967e8);
+    }
+
+    #[test]
+    fn test_input_hash_differs() {
+        assert_ne!(input_hash("A Y\nB X\nC Z"), input_hash("A Y\nB X\nC Y"));
+    }
+
+    #[test]
+    fn test_split_on_blank_line() {
+        assert_eq!(split_on_blank_line("1\n2\n\n3"), vec!["1\n2", "3"]);
+        assert_eq!(split_on_blank_line("1\n\n\n\n2\n"), vec!["1", "2"]);
+        assert_eq!(split_on_blank_line("\n\n1\n  \n2\n\n"), vec!["1", "2"]);
+        assert_eq!(split_on_blank_line(""), Vec::<&str>::new());
+    }
+
+    #[test]
+    fn test_split_on_blank_line_crlf() {
+        assert_eq!(
+            split_on_blank_line("1\r\n2\r\n\r\n\r\n3\r\n"),
+            vec!["1\r\n2", "3"]
+        );
+    }
+
+    #[test]
+    fn test_split_on_blank_line_keeps_indentation() {
+        assert_eq!(
+            split_on_blank_line("    [D]\n[N] [C]\n\nmove 1 from 2 to 1"),
+            vec!["    [D]\n[N] [C]", "move 1 from 2 to 1"]
+        );
+    }
+}
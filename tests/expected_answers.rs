@@ -0,0 +1,52 @@
+//! Regression tests against known answers.
+//!
+//! Puzzle inputs differ per person, so the answers are keyed by the hash of the input they belong
+//! to. A day whose input isn't in the table is skipped, add a row to check your own input.
+#![cfg(feature = "bundled-input")]
+
+use advent_of_code_2022::util::input_hash;
+use advent_of_code_2022::{day_01, day_02, day_03, day_04, day_05, DayResult};
+
+/// `(input hash, part one, part two)`
+const EXPECTED_ANSWERS: &[(u64, &str, &str)] = &[
+    (0xc11e30bc37d51219, "70698", "206643"),
+    (0x179fa65e88ee704a, "12535", "15457"),
+    (0x368cf25520593ded, "7863", "2488"),
+    (0x863e968454c5e2fd, "490", "921"),
+    (0x79032f55089c46be, "JRVNHHCSJ", "GNFBSBJLH"),
+];
+
+fn check(input: &str, solve: fn(&str) -> DayResult) {
+    let hash = input_hash(input);
+    let Some((_, part_one, part_two)) = EXPECTED_ANSWERS.iter().find(|(h, _, _)| *h == hash) else {
+        eprintln!("No expected answers for input {:#018x}, skipping", hash);
+        return;
+    };
+
+    assert_eq!(solve(input), DayResult::new(part_one, part_two));
+}
+
+#[test]
+fn test_day_01() {
+    check(day_01::INPUT, day_01::solve);
+}
+
+#[test]
+fn test_day_02() {
+    check(day_02::INPUT, day_02::solve);
+}
+
+#[test]
+fn test_day_03() {
+    check(day_03::INPUT, day_03::solve);
+}
+
+#[test]
+fn test_day_04() {
+    check(day_04::INPUT, day_04::solve);
+}
+
+#[test]
+fn test_day_05() {
+    check(day_05::INPUT, day_05::solve);
+}
@@ -0,0 +1,36 @@
+//! Checks that the days build and solve without the bundled inputs, run it with
+//! `cargo test --no-default-features`.
+#![cfg(not(feature = "bundled-input"))]
+
+use advent_of_code_2022::{day_01, day_02, day_03, day_04, day_05, DayResult};
+
+#[test]
+fn solve_examples() {
+    assert_eq!(
+        day_01::solve("1000\n2000\n3000\n\n4000\n\n5000\n6000\n\n7000\n8000\n9000\n\n10000"),
+        DayResult::new(24000, 45000)
+    );
+    assert_eq!(day_02::solve("A Y\nB X\nC Z"), DayResult::new(15, 12));
+    assert_eq!(
+        day_03::solve(
+            "vJrwpWtwJgWrhcsFMMfFFhFp\n\
+             jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL\n\
+             PmmdzqPrVvPwwTWBwg\n\
+             wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn\n\
+             ttgJtRGJQctTZtZT\n\
+             CrZsJsPPZsGzwwsLwLmpwMDw"
+        ),
+        DayResult::new(157, 70)
+    );
+    assert_eq!(
+        day_04::solve("2-4,6-8\n2-3,4-5\n5-7,7-9\n2-8,3-7\n6-6,4-6\n2-6,4-8"),
+        DayResult::new(2, 4)
+    );
+    assert_eq!(
+        day_05::solve(
+            "    [D]    \n[N] [C]    \n[Z] [M] [P]\n 1   2   3 \n\n\
+             move 1 from 2 to 1\nmove 3 from 1 to 3\nmove 2 from 2 to 1\nmove 1 from 1 to 2"
+        ),
+        DayResult::new("CMZ", "MCD")
+    );
+}